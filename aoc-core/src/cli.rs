@@ -0,0 +1,133 @@
+use std::env;
+use std::fmt::Display;
+use std::process;
+use std::str::FromStr;
+
+/// The command line, consumed one option at a time.  Each binary pulls out
+/// the options it knows about and then calls [`Args::finish`], which
+/// rejects anything left over and hands back the optional INPUT path.
+#[derive(Debug)]
+pub struct Args {
+    program: String,
+    args: Vec<String>,
+}
+impl Args {
+    pub fn from_env() -> Args {
+        let mut args = env::args();
+        let program = args.next().unwrap_or_else(|| String::from("aoc"));
+        Args::new(&program, args.collect())
+    }
+
+    pub fn new(program: &str, args: Vec<String>) -> Args {
+        Args {
+            program: String::from(program),
+            args,
+        }
+    }
+
+    pub fn program(&self) -> &str {
+        self.program.as_str()
+    }
+
+    /// Removes every occurrence of any of `names`, returning whether there
+    /// were any.
+    pub fn flag(&mut self, names: &[&str]) -> bool {
+        self.count(names) > 0
+    }
+
+    /// Removes every occurrence of any of `names`, returning how many there
+    /// were.
+    pub fn count(&mut self, names: &[&str]) -> usize {
+        let before = self.args.len();
+        self.args.retain(|a| !names.contains(&a.as_str()));
+        before - self.args.len()
+    }
+
+    /// Removes `name <value>` and parses the value.  If the option is given
+    /// more than once the last one wins.
+    pub fn value<T>(&mut self, name: &str) -> Result<Option<T>, String>
+    where T: FromStr, T::Err: Display {
+        Ok(self.values(name)?.pop())
+    }
+
+    /// Removes every `name <value>` and parses the values in order.
+    pub fn values<T>(&mut self, name: &str) -> Result<Vec<T>, String>
+    where T: FromStr, T::Err: Display {
+        let mut values = Vec::new();
+        while let Some(i) = self.args.iter().position(|a| a == name) {
+            if i + 1 >= self.args.len() {
+                return Err(format!("{} needs a value", name));
+            }
+            let value = self.args.remove(i + 1);
+            self.args.remove(i);
+            let parsed = value.parse::<T>()
+                .map_err(|e| format!("invalid value '{}' for {}: {}", value, name, e))?;
+            values.push(parsed);
+        }
+        Ok(values)
+    }
+
+    /// Checks that only the optional INPUT path is left and returns it.
+    pub fn finish(self) -> Result<Option<String>, String> {
+        let mut input = None;
+        for arg in self.args {
+            if arg.starts_with("--") || (arg.starts_with('-') && arg != "-") {
+                return Err(format!("unknown option '{}'", arg));
+            }
+            if input.is_some() {
+                return Err(format!("unexpected argument '{}'", arg));
+            }
+            input = Some(arg);
+        }
+        Ok(input)
+    }
+}
+
+/// Prints `message` and the usage text to stderr and exits with status 2.
+pub fn exit_with_usage(message: &str, usage: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, usage);
+    process::exit(2);
+}
+
+/// Unwraps a command line result, or exits with the usage text.
+pub fn or_usage<T>(result: Result<T, String>, usage: &str) -> T {
+    result.unwrap_or_else(|message| exit_with_usage(&message, usage))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(raw: &str) -> Args {
+        Args::new("aoc", raw.split_whitespace().map(String::from).collect())
+    }
+
+    #[test]
+    fn test_values_and_input() {
+        let mut args = args("--slope 1,1 day3/input --slope 3,1 --verbose");
+
+        assert!(args.flag(&["-v", "--verbose"]));
+        assert!(!args.flag(&["-v", "--verbose"]));
+        assert_eq!(Ok(vec![String::from("1,1"), String::from("3,1")]), args.values::<String>("--slope"));
+        assert_eq!(Ok(Some(String::from("day3/input"))), args.finish());
+    }
+
+    #[test]
+    fn test_value_parse_error() {
+        let mut args = args("--day seven");
+
+        assert_eq!(
+            Err(String::from("invalid value 'seven' for --day: invalid digit found in string")),
+            args.value::<u8>("--day")
+        );
+    }
+
+    #[test]
+    fn test_finish_rejects_leftovers() {
+        assert!(args("--day").value::<u8>("--day").is_err());
+        assert!(args("--unknown").finish().is_err());
+        assert!(args("a b").finish().is_err());
+        assert_eq!(Ok(Some(String::from("-"))), args("-").finish());
+        assert_eq!(Ok(None), args("").finish());
+    }
+}
@@ -0,0 +1,188 @@
+pub mod cli;
+pub mod logging;
+pub mod report;
+
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read};
+use std::process;
+use std::str::FromStr;
+
+/// A single day's puzzle.  The raw input is parsed once and then handed to
+/// each part, so every day can be driven the same way.
+pub trait Solution {
+    type Input;
+    type Part1: Display;
+    type Part2: Display;
+
+    fn parse(raw_input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Self::Part1;
+    fn part2(input: &Self::Input) -> Self::Part2;
+}
+
+/// Where and why a puzzle input couldn't be parsed.  Lines and columns are
+/// 1-based, and `text` is the piece of input that was rejected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+impl ParseError {
+    pub fn new(line: usize, column: usize, text: &str, message: &str) -> ParseError {
+        ParseError {
+            line,
+            column,
+            text: String::from(text),
+            message: String::from(message),
+        }
+    }
+
+    /// Moves an error raised while parsing a single line onto `line` of the
+    /// whole input.
+    pub fn on_line(self, line: usize) -> ParseError {
+        ParseError { line, ..self }
+    }
+}
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}: {:?}", self.line, self.column, self.message, self.text)
+    }
+}
+impl Error for ParseError {}
+
+/// The 1-based column at which `part`, a slice borrowed from `line`, starts.
+pub fn column_of(line: &str, part: &str) -> usize {
+    part.as_ptr() as usize - line.as_ptr() as usize + 1
+}
+
+/// Which half of a puzzle to answer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Part {
+    One,
+    Two,
+    Both,
+}
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Part, String> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            "both" => Ok(Part::Both),
+            _ => Err(format!("invalid part '{}', expected 1, 2 or both", s)),
+        }
+    }
+}
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+            Part::Both => write!(f, "both"),
+        }
+    }
+}
+
+/// Reads the whole puzzle input from `path`, or from stdin when there's no
+/// path or it is `-`.
+pub fn read_input(path: Option<&str>) -> io::Result<String> {
+    match path {
+        None | Some("-") => {
+            let mut raw_input = String::new();
+            io::stdin().read_to_string(&mut raw_input)?;
+            Ok(raw_input)
+        },
+        Some(path) => fs::read_to_string(path),
+    }
+}
+
+/// Opens the puzzle input at `path`, or stdin when there's no path or it is
+/// `-`, for days that read their input a line at a time.
+pub fn open_input(path: Option<&str>) -> io::Result<Box<dyn BufRead>> {
+    match path {
+        None | Some("-") => Ok(Box::new(BufReader::new(io::stdin()))),
+        Some(path) => Ok(Box::new(BufReader::new(fs::File::open(path)?))),
+    }
+}
+
+/// Parses the input and prints the answers for the requested part(s).
+pub fn solve<S: Solution>(raw_input: &str, part: Part) -> Result<(), ParseError> {
+    let input = S::parse(raw_input)?;
+    print_answers::<S>(&input, part);
+    Ok(())
+}
+
+/// Prints the answers for the requested part(s) from input that has already
+/// been parsed, for days that can parse their input in more than one way.
+pub fn print_answers<S: Solution>(input: &S::Input, part: Part) {
+    if part != Part::Two {
+        println!("Solution Part 1:  {}", S::part1(input));
+    }
+    if part != Part::One {
+        println!("Solution Part 2:  {}", S::part2(input));
+    }
+}
+
+/// Unwraps `result`, or reports the error against the input it came from and
+/// exits with status 1.
+pub fn or_exit<T, E: Display>(result: Result<T, E>, path: Option<&str>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("error: {}: {}", path.unwrap_or("stdin"), e);
+        process::exit(1);
+    })
+}
+
+/// Like [`read_input`], but exits with status 1 if the input can't be read.
+pub fn load_input(path: Option<&str>) -> String {
+    or_exit(read_input(path), path)
+}
+
+/// Entry point for the per-day binaries: reads the input file named on the
+/// command line (or stdin) and prints both answers.
+pub fn run<S: Solution>() {
+    let mut args = cli::Args::from_env();
+    let usage = format!("\
+Usage: {} [-v|-q] [INPUT]
+
+Options:
+{}
+
+Reads the puzzle input from INPUT, or from stdin when INPUT is omitted or `-`.", args.program(), logging::USAGE);
+    logging::init_from_args(&mut args);
+    let path = cli::or_usage(args.finish(), &usage);
+
+    let raw_input = load_input(path.as_deref());
+    or_exit(solve::<S>(raw_input.as_str(), Part::Both), path.as_deref());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_part() {
+        assert_eq!(Ok(Part::One), "1".parse());
+        assert_eq!(Ok(Part::Two), "2".parse());
+        assert_eq!(Ok(Part::Both), "both".parse());
+        assert!("3".parse::<Part>().is_err());
+    }
+
+    #[test]
+    fn test_column_of() {
+        let line = "1-3 a: abcde";
+        let password = &line[7..];
+
+        assert_eq!(8, column_of(line, password));
+    }
+
+    #[test]
+    fn test_parse_error_display() {
+        let error = ParseError::new(1, 3, "x", "unknown map symbol").on_line(4);
+
+        assert_eq!("line 4, column 3: unknown map symbol: \"x\"", error.to_string());
+    }
+}
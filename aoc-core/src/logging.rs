@@ -0,0 +1,70 @@
+use log::{LevelFilter, Log, Metadata, Record};
+
+use crate::cli::Args;
+
+/// Usage lines for the flags that [`init_from_args`] understands.
+// not `"\` and a new line, which would drop the first line's indent
+pub const USAGE: &str = "    -v, --verbose      print diagnostics to stderr; repeat (-vv, -vvv) for more
+    -q, --quiet        only print errors to stderr";
+
+// Diagnostics go to stderr so they never get mixed up with the answers.
+struct StderrLogger;
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{}] {}", record.level(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: StderrLogger = StderrLogger;
+
+/// The level to log at for a number of `-v` flags: warnings by default, then
+/// info, debug and trace.  `quiet` wins over any `-v`.
+pub fn level(verbose: usize, quiet: bool) -> LevelFilter {
+    if quiet {
+        return LevelFilter::Error;
+    }
+    match verbose {
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+/// Sends the `log` macros to stderr at `level`.
+pub fn init(level: LevelFilter) {
+    // a logger can only be installed once; later calls just change the level
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(level);
+}
+
+/// Pulls the verbosity flags out of the command line and sets up logging.
+pub fn init_from_args(args: &mut Args) {
+    let verbose = args.count(&["-v", "--verbose"])
+        + 2 * args.count(&["-vv"])
+        + 3 * args.count(&["-vvv"]);
+    let quiet = args.flag(&["-q", "--quiet"]);
+    init(level(verbose, quiet));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level() {
+        assert_eq!(LevelFilter::Warn, level(0, false));
+        assert_eq!(LevelFilter::Info, level(1, false));
+        assert_eq!(LevelFilter::Debug, level(2, false));
+        assert_eq!(LevelFilter::Trace, level(5, false));
+        assert_eq!(LevelFilter::Error, level(2, true));
+    }
+}
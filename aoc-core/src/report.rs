@@ -0,0 +1,45 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// How a report is written out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+}
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown report format '{}', expected text or json", s)),
+        }
+    }
+}
+
+/// A report that can be written in any [`Format`]: `Display` gives the
+/// human-readable form and [`Render::to_json`] the machine-readable one.
+pub trait Render: Display {
+    fn to_json(&self) -> String;
+
+    fn render(&self, format: Format) -> String {
+        match format {
+            Format::Text => self.to_string(),
+            Format::Json => self.to_json(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(Ok(Format::Text), "text".parse());
+        assert_eq!(Ok(Format::Json), "json".parse());
+        assert_eq!(Err(String::from("unknown report format 'xml', expected text or json")), "xml".parse::<Format>());
+    }
+}
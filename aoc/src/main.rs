@@ -0,0 +1,102 @@
+use aoc_core::cli::{self, Args};
+use aoc_core::{logging, Part};
+
+fn usage() -> String {
+    format!("\
+Usage: aoc --day <1-7> [--part <1|2|both>] [-v|-q] [INPUT]
+
+Options:
+    --day <N>          the puzzle to run, 1 through 7
+    --part <P>         which part to answer: 1, 2 or both (default: both)
+{}
+    -h, --help         print this message
+
+Reads the puzzle input from INPUT, or from stdin when INPUT is omitted or `-`.", logging::USAGE)
+}
+
+fn main() {
+    let usage = usage();
+    let mut args = Args::from_env();
+    if args.flag(&["-h", "--help"]) {
+        println!("{}", usage);
+        return;
+    }
+    logging::init_from_args(&mut args);
+    let options = cli::or_usage(Options::parse(args), &usage);
+
+    let path = options.input.as_deref();
+    let raw_input = aoc_core::load_input(path);
+    let raw_input = raw_input.as_str();
+    let result = match options.day {
+        1 => aoc_core::solve::<day1::Day1>(raw_input, options.part),
+        2 => aoc_core::solve::<day2::Day2>(raw_input, options.part),
+        3 => aoc_core::solve::<day3::Day3>(raw_input, options.part),
+        4 => aoc_core::solve::<day4::Day4>(raw_input, options.part),
+        5 => aoc_core::solve::<day5::Day5>(raw_input, options.part),
+        6 => aoc_core::solve::<day6::Day6>(raw_input, options.part),
+        7 => aoc_core::solve::<day7::Day7>(raw_input, options.part),
+        _ => unreachable!("Options::parse only accepts days 1 through 7"),
+    };
+    aoc_core::or_exit(result, path);
+}
+
+#[derive(Debug, PartialEq)]
+struct Options {
+    day: u8,
+    part: Part,
+    input: Option<String>,
+}
+impl Options {
+    fn parse(mut args: Args) -> Result<Options, String> {
+        let day = match args.value::<u8>("--day")? {
+            Some(day) if (1..=7).contains(&day) => day,
+            Some(day) => return Err(format!("invalid day '{}', expected 1 through 7", day)),
+            None => return Err(String::from("missing required option --day")),
+        };
+        let part = args.value("--part")?.unwrap_or(Part::Both);
+        let input = args.finish()?;
+
+        Ok(Options { day, part, input })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(raw: &str) -> Args {
+        Args::new("aoc", raw.split_whitespace().map(String::from).collect())
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let expected = Options {
+            day: 3,
+            part: Part::Two,
+            input: Some(String::from("day3/input")),
+        };
+
+        assert_eq!(Ok(expected), Options::parse(args("--day 3 --part 2 day3/input")));
+    }
+
+    #[test]
+    fn test_parse_args_defaults_to_both_parts_from_stdin() {
+        let expected = Options {
+            day: 7,
+            part: Part::Both,
+            input: None,
+        };
+
+        assert_eq!(Ok(expected), Options::parse(args("--day 7")));
+    }
+
+    #[test]
+    fn test_parse_args_rejects_bad_input() {
+        assert!(Options::parse(args("")).is_err());
+        assert!(Options::parse(args("--day")).is_err());
+        assert!(Options::parse(args("--day 8")).is_err());
+        assert!(Options::parse(args("--day 1 --part 3")).is_err());
+        assert!(Options::parse(args("--day 1 --frobnicate")).is_err());
+        assert!(Options::parse(args("--day 1 a b")).is_err());
+    }
+}
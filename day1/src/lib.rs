@@ -0,0 +1,390 @@
+use std::fmt;
+use std::ops::Range;
+use aoc_core::{ParseError, Solution};
+
+const TARGET: i64 = 2020;
+
+pub struct Day1;
+impl Solution for Day1 {
+    type Input = Vec<i64>;
+    type Part1 = KSum;
+    type Part2 = KSum;
+
+    fn parse(raw_input: &str) -> Result<Vec<i64>, ParseError> {
+        raw_input.lines()
+            .enumerate()
+            .map(|(i, s)| s.trim().parse::<i64>()
+                .map_err(|_| ParseError::new(i + 1, 1, s, "expected an expense amount")))
+            .collect()
+    }
+
+    fn part1(input: &Vec<i64>) -> KSum {
+        KSum::find(input, 2, TARGET)
+    }
+
+    fn part2(input: &Vec<i64>) -> KSum {
+        KSum::find(input, 3, TARGET)
+    }
+}
+
+/// The entries found for a k-sum search, printed as their product.
+pub struct KSum {
+    k: usize,
+    target: i64,
+    entries: Option<Vec<i64>>,
+}
+impl KSum {
+    fn find(input: &[i64], k: usize, target: i64) -> KSum {
+        KSum {
+            k,
+            target,
+            entries: find_k_sum(input, k, target),
+        }
+    }
+}
+impl fmt::Display for KSum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.entries {
+            Some(entries) => {
+                let factors: Vec<String> = entries.iter().map(|e| e.to_string()).collect();
+                match entries.iter().try_fold(1i64, |product, e| product.checked_mul(*e)) {
+                    Some(product) => write!(f, "{} = {}", factors.join(" * "), product),
+                    None => write!(f, "{} overflows an i64", factors.join(" * ")),
+                }
+            },
+            None => write!(f, "no solution: no {} entries sum to {}", self.k, self.target),
+        }
+    }
+}
+
+/// Finds `k` entries (each used at most once) that add up to `target`,
+/// returned in ascending order, or `None` if there aren't any.
+///
+/// The entries are sorted once, then all but the last two are fixed in turn
+/// and the final pair is closed in on from both ends, so a pair search is
+/// O(n log n) and each extra entry costs another factor of n.
+pub fn find_k_sum(input: &[i64], k: usize, target: i64) -> Option<Vec<i64>> {
+    let mut sorted = input.to_vec();
+    sorted.sort_unstable();
+
+    let mut entries = Vec::with_capacity(k);
+    if k_sum(&sorted, k, target as i128, &mut entries) {
+        Some(entries)
+    } else {
+        None
+    }
+}
+
+// Sums are widened to i128 so pruning can't overflow on extreme entries.
+fn k_sum(sorted: &[i64], k: usize, target: i128, entries: &mut Vec<i64>) -> bool {
+    if k == 0 {
+        return target == 0;
+    }
+    if sorted.len() < k {
+        return false;
+    }
+
+    // the k smallest entries are already too big, or the k largest too small
+    let smallest: i128 = sorted[..k].iter().map(|&e| e as i128).sum();
+    let largest: i128 = sorted[sorted.len() - k..].iter().map(|&e| e as i128).sum();
+    if target < smallest || target > largest {
+        return false;
+    }
+
+    match k {
+        1 => {
+            if sorted.binary_search_by(|e| (*e as i128).cmp(&target)).is_ok() {
+                entries.push(target as i64);
+                true
+            } else {
+                false
+            }
+        },
+        2 => {
+            let (mut lo, mut hi) = (0, sorted.len() - 1);
+            while lo < hi {
+                let sum = sorted[lo] as i128 + sorted[hi] as i128;
+                if sum == target {
+                    entries.push(sorted[lo]);
+                    entries.push(sorted[hi]);
+                    return true;
+                } else if sum < target {
+                    lo += 1;
+                } else {
+                    hi -= 1;
+                }
+            }
+            false
+        },
+        _ => {
+            for i in 0..=sorted.len() - k {
+                // an equal entry was already tried with more choices after it
+                if i > 0 && sorted[i] == sorted[i - 1] {
+                    continue;
+                }
+                entries.push(sorted[i]);
+                if k_sum(&sorted[i + 1..], k - 1, target - sorted[i] as i128, entries) {
+                    return true;
+                }
+                entries.pop();
+            }
+            false
+        },
+    }
+}
+
+/// Every set of `k` distinct entries that adds up to `target`, as their
+/// indexes into `input` in ascending order.  Repeated values are separate
+/// entries, so `[1010, 1010, 1010]` has three pairs summing to 2020.
+/// `limit` stops the search after that many solutions.
+pub fn k_sum_solutions(input: &[i64], k: usize, target: i64, limit: Option<usize>) -> KSumSolutions {
+    let mut sorted: Vec<(i64, usize)> = input.iter()
+        .enumerate()
+        .map(|(i, &e)| (e, i))
+        .collect();
+    sorted.sort_unstable();
+
+    KSumSolutions {
+        sorted,
+        k,
+        target: target as i128,
+        limit,
+        found: 0,
+        started: false,
+        prefix: Vec::new(),
+        matches: 0..0,
+    }
+}
+
+/// Lazily walks every combination of the first `k - 1` entries (in value
+/// order) and finishes each with a binary search for the entries that make
+/// up the rest of the target.  See [`k_sum_solutions`].
+pub struct KSumSolutions {
+    // entries paired with their original index, sorted by value
+    sorted: Vec<(i64, usize)>,
+    k: usize,
+    target: i128,
+    limit: Option<usize>,
+    found: usize,
+    started: bool,
+    // positions in `sorted` picked for all but the last entry
+    prefix: Vec<usize>,
+    // positions in `sorted` that complete the current prefix
+    matches: Range<usize>,
+}
+impl KSumSolutions {
+    // Moves on to the next combination of prefix positions, returning false
+    // once they have all been tried.
+    fn advance(&mut self) -> bool {
+        let n = self.sorted.len();
+        let m = self.k - 1;
+        if !self.started {
+            self.started = true;
+            if n < self.k {
+                return false;
+            }
+            self.prefix = (0..m).collect();
+            return true;
+        }
+
+        // slot i has to leave room for the slots after it and the last entry
+        let mut i = m;
+        while i > 0 {
+            i -= 1;
+            if self.prefix[i] < n - self.k + i {
+                self.prefix[i] += 1;
+                for j in i + 1..m {
+                    self.prefix[j] = self.prefix[j - 1] + 1;
+                }
+                return true;
+            }
+        }
+        false
+    }
+
+    fn find_matches(&mut self) {
+        let remaining = self.target - self.prefix.iter()
+            .map(|&p| self.sorted[p].0 as i128)
+            .sum::<i128>();
+        let start = self.prefix.last().map_or(0, |&p| p + 1);
+        let rest = &self.sorted[start..];
+
+        let lo = rest.partition_point(|&(e, _)| (e as i128) < remaining);
+        let hi = rest.partition_point(|&(e, _)| (e as i128) <= remaining);
+        self.matches = start + lo..start + hi;
+    }
+}
+impl Iterator for KSumSolutions {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        if self.limit.is_some_and(|limit| self.found >= limit) {
+            return None;
+        }
+        if self.k == 0 {
+            // the empty set is the only way to pick nothing
+            let first = !self.started;
+            self.started = true;
+            return if first && self.target == 0 {
+                self.found += 1;
+                Some(Vec::new())
+            } else {
+                None
+            };
+        }
+
+        loop {
+            if let Some(last) = self.matches.next() {
+                let mut indexes: Vec<usize> = self.prefix.iter()
+                    .chain(std::iter::once(&last))
+                    .map(|&p| self.sorted[p].1)
+                    .collect();
+                indexes.sort_unstable();
+                self.found += 1;
+                return Some(indexes);
+            }
+            if !self.advance() {
+                return None;
+            }
+            self.find_matches();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_sums_to_2020_1721_299() {
+        let expected = Some(vec![299, 1721]);
+        let raw_input = "\
+1721
+979
+366
+299
+675
+1456";
+        let input = Day1::parse(raw_input).unwrap();
+
+        assert_eq!(expected, find_k_sum(&input, 2, 2020));
+    }
+
+    #[test]
+    fn test_find_sums_to_2020_1720_300() {
+        let expected = Some(vec![300, 1720]);
+        let raw_input = "\
+1720
+979
+366
+300
+675
+1456";
+        let input = Day1::parse(raw_input).unwrap();
+
+        assert_eq!(expected, find_k_sum(&input, 2, 2020));
+    }
+
+    #[test]
+    fn test_find_three_sums_to_2020_1721_299() {
+        let expected = Some(vec![366, 675, 979]);
+        let raw_input = "\
+1721
+979
+366
+299
+675
+1456";
+        let input = Day1::parse(raw_input).unwrap();
+
+        assert_eq!(expected, find_k_sum(&input, 3, 2020));
+    }
+
+    #[test]
+    fn test_find_k_sum_no_solution() {
+        let input = vec![1721, 979, 366, 299, 675, 1456];
+
+        assert_eq!(None, find_k_sum(&input, 2, 1));
+        assert_eq!(None, find_k_sum(&input, 7, 2020));
+        assert_eq!(None, find_k_sum(&[1010], 2, 2020));
+    }
+
+    #[test]
+    fn test_find_k_sum_other_targets() {
+        let input = vec![-5, 3, 3, 10, 42, 7];
+
+        assert_eq!(Some(vec![3, 3]), find_k_sum(&input, 2, 6));
+        assert_eq!(Some(vec![-5, 3, 7, 42]), find_k_sum(&input, 4, 47));
+        assert_eq!(Some(vec![]), find_k_sum(&input, 0, 0));
+    }
+
+    #[test]
+    fn test_find_k_sum_large_input() {
+        // every entry is even, so an odd target can never be hit
+        let input: Vec<i64> = (0..300_000).map(|i| i * 2).collect();
+
+        assert_eq!(None, find_k_sum(&input, 2, 300_001));
+        assert_eq!(Some(vec![0, 599_998]), find_k_sum(&input, 2, 599_998));
+    }
+
+    #[test]
+    fn test_k_sum_solutions_repeated_values() {
+        let input = vec![1010, 1010, 1010, 5, 2015];
+        let expected = vec![vec![3, 4], vec![0, 1], vec![0, 2], vec![1, 2]];
+
+        let solutions: Vec<Vec<usize>> = k_sum_solutions(&input, 2, 2020, None).collect();
+
+        assert_eq!(expected, solutions);
+    }
+
+    #[test]
+    fn test_k_sum_solutions_three_entries() {
+        let input = vec![1, 1, 2, 2, 3];
+        let expected = vec![
+            vec![0, 1, 4],
+            vec![0, 2, 3],
+            vec![1, 2, 3],
+        ];
+
+        let mut solutions: Vec<Vec<usize>> = k_sum_solutions(&input, 3, 5, None).collect();
+        solutions.sort();
+
+        assert_eq!(expected, solutions);
+    }
+
+    #[test]
+    fn test_k_sum_solutions_limit() {
+        let input = vec![1010; 10];
+
+        assert_eq!(45, k_sum_solutions(&input, 2, 2020, None).count());
+        assert_eq!(3, k_sum_solutions(&input, 2, 2020, Some(3)).count());
+        assert_eq!(0, k_sum_solutions(&input, 2, 2021, None).count());
+    }
+
+    #[test]
+    fn test_k_sum_solutions_edge_cases() {
+        assert_eq!(vec![vec![1]], k_sum_solutions(&[4, 7], 1, 7, None).collect::<Vec<_>>());
+        assert_eq!(vec![Vec::<usize>::new()], k_sum_solutions(&[4, 7], 0, 0, None).collect::<Vec<_>>());
+        assert_eq!(0, k_sum_solutions(&[4, 7], 3, 11, None).count());
+        assert_eq!(0, k_sum_solutions(&[], 2, 0, None).count());
+    }
+
+    #[test]
+    fn test_ksum_display() {
+        assert_eq!("299 * 1721 = 514579", KSum::find(&[1721, 299], 2, 2020).to_string());
+        assert_eq!("no solution: no 2 entries sum to 2020", KSum::find(&[1721], 2, 2020).to_string());
+        assert_eq!("-999999997980 * 1000000000000 overflows an i64",
+            KSum::find(&[1000000000000, -999999997980, 5], 2, 2020).to_string());
+    }
+
+    #[test]
+    fn test_parse_reports_bad_line() {
+        let raw_input = "\
+1721
+97x9
+366";
+        let expected = ParseError::new(2, 1, "97x9", "expected an expense amount");
+
+        assert_eq!(Err(expected), Day1::parse(raw_input));
+    }
+}
@@ -0,0 +1,161 @@
+pub mod policy;
+pub mod report;
+
+use std::fmt;
+use aoc_core::{column_of, ParseError, Solution};
+use policy::{CountRange, Indexing, PasswordPolicy, Positional};
+
+pub struct Day2;
+impl Solution for Day2 {
+    type Input = Vec<Password>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(raw_input: &str) -> Result<Vec<Password>, ParseError> {
+        raw_input.lines()
+            .enumerate()
+            .map(|(i, line)| match Password::new(line) {
+                Ok(password) => Ok(Password { line: i + 1, ..password }),
+                Err(e) => Err(e.on_line(i + 1)),
+            })
+            .collect()
+    }
+
+    fn part1(input: &Vec<Password>) -> usize {
+        count_valid_passwords(input, &CountRange)
+    }
+
+    fn part2(input: &Vec<Password>) -> usize {
+        count_valid_passwords(input, &Positional(Indexing::Char))
+    }
+}
+
+// The policy written at the start of each line.  What `min` and `max` mean
+// depends on the policy it is checked with.
+#[derive(Debug)]
+struct LinePolicy {
+    min: i32,
+    max: i32,
+    char: char,
+}
+
+impl LinePolicy {
+    // example input:
+    //    1-3 a
+    fn new(policy_str: &str) -> Result<LinePolicy, ParseError> {
+        let error = |part: &str, message: &str| {
+            ParseError::new(1, column_of(policy_str, part), part, message)
+        };
+
+        let (minmax, char_str) = policy_str.split_once(' ')
+            .ok_or_else(|| error(policy_str, "expected a policy like `1-3 a`"))?;
+        let (min, max) = minmax.split_once('-')
+            .ok_or_else(|| error(minmax, "expected a range like `1-3`"))?;
+        let min = min.parse::<i32>().map_err(|_| error(min, "expected a number"))?;
+        let max = max.parse::<i32>().map_err(|_| error(max, "expected a number"))?;
+
+        let mut chars = char_str.chars();
+        let char = match (chars.next(), chars.next()) {
+            (Some(c), None) => c,
+            _ => return Err(error(char_str, "expected a single character")),
+        };
+
+        Ok(LinePolicy { min, max, char })
+    }
+}
+impl fmt::Display for LinePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{} {}", self.min, self.max, self.char)
+    }
+}
+
+#[derive(Debug)]
+pub struct Password {
+    // the line of the database this password was read from
+    line: usize,
+    policy: LinePolicy,
+    password: String,
+}
+
+impl Password {
+    // example input:
+    //    1-3 a: abcde
+    fn new(line: &str) -> Result<Password, ParseError> {
+        let (policy_str, password) = line.split_once(": ")
+            .ok_or_else(|| ParseError::new(1, 1, line, "expected `<policy>: <password>`"))?;
+        let policy = LinePolicy::new(policy_str)?;
+        let password = String::from(password);
+
+        Ok(Password {
+            line: 1,
+            policy,
+            password
+        })
+    }
+}
+
+/// How many of the passwords satisfy `policy`.
+pub fn count_valid_passwords(input: &[Password], policy: &dyn PasswordPolicy) -> usize {
+    input.iter()
+        .filter(|p| policy.is_valid(p))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_valid_passwords_2() {
+        let expected = 2;
+        let raw_input = "\
+1-3 a: abcde
+1-3 b: cdefg
+2-9 c: ccccccccc";
+        let input = Day2::parse(raw_input).unwrap();
+
+        assert_eq!(expected, count_valid_passwords(&input, &CountRange));
+    }
+
+    #[test]
+    fn test_count_valid_passwords_3() {
+        let expected = 3;
+        let raw_input = "\
+1-3 a: abcde
+1-3 b: cdefg
+2-9 c: ccccccccc
+2-9 z: zzzzzzzzz";
+        let input = Day2::parse(raw_input).unwrap();
+
+        assert_eq!(expected, count_valid_passwords(&input, &CountRange));
+    }
+
+    #[test]
+    fn test_count_valid_passwords_part2() {
+        let expected = 1;
+        let raw_input = "\
+1-3 a: abcde
+1-3 b: cdefg
+2-9 c: ccccccccc";
+        let input = Day2::parse(raw_input).unwrap();
+
+        assert_eq!(expected, count_valid_passwords(&input, &Positional(Indexing::Char)));
+    }
+
+    #[test]
+    fn test_parse_reports_bad_policy() {
+        let raw_input = "\
+1-3 a: abcde
+1-x b: cdefg";
+        let expected = ParseError::new(2, 3, "x", "expected a number");
+
+        assert_eq!(expected, Day2::parse(raw_input).unwrap_err());
+    }
+
+    #[test]
+    fn test_parse_reports_missing_password() {
+        let expected = ParseError::new(1, 1, "1-3 a abcde", "expected `<policy>: <password>`");
+
+        assert_eq!(expected, Password::new("1-3 a abcde").unwrap_err());
+    }
+}
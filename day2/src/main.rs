@@ -1,115 +1,71 @@
-use std::env;
-use std::fs;
+use aoc_core::cli::{self, Args};
+use aoc_core::report::{Format, Render};
+use aoc_core::{logging, Solution};
+use day2::policy::{CountRange, Indexing, PasswordPolicy, Positional, Ruleset};
+use day2::report::Report;
+use day2::{count_valid_passwords, Day2};
+
+fn usage() -> String {
+    format!("\
+Usage: day2 [--policy <RULES>]... [--report <text|json>] [-v|-q] [INPUT]
+
+Without --policy or --report, prints the answers to both parts of the puzzle.
+
+Options:
+    --policy <RULES>   audit the database against a ruleset, given as
+                       policies separated by `;` which must all pass:
+                           count               the letter count is in range
+                           positional[=UNIT]   exactly one position has the letter,
+                                               counting positions by byte, char
+                                               (the default) or grapheme
+                           min-length=N        at least N characters
+                           classes=C,...       one of each of lower, upper,
+                                               digit and symbol
+                           forbid=S,...        none of these substrings
+                           regex=PATTERN       matches the regular expression;
+                                               takes the rest of RULES, so it
+                                               goes last and can hold `;`
+                       can be given more than once to compare rulesets
+    --report <FORMAT>  print a pass/fail line, with the reason, for every
+                       password and ruleset (default rulesets: count and
+                       positional), as text or json
+{}
+
+Reads the password database from INPUT, or from stdin when INPUT is omitted or `-`.", logging::USAGE)
+}
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let usage = usage();
+    let mut args = Args::from_env();
+    logging::init_from_args(&mut args);
+    let rulesets: Vec<Ruleset> = cli::or_usage(args.values("--policy"), &usage);
+    let format: Option<Format> = cli::or_usage(args.value("--report"), &usage);
+    let path = cli::or_usage(args.finish(), &usage);
+
+    let raw_input = aoc_core::load_input(path.as_deref());
+    if rulesets.is_empty() && format.is_none() {
+        aoc_core::or_exit(aoc_core::solve::<Day2>(&raw_input, aoc_core::Part::Both), path.as_deref());
+        return;
+    }
 
-    let filename = &args[1];
-    let contents = fs::read_to_string(filename)
-        .expect("Something went wrong reading the file");
-    let input: Vec<Password> = contents.lines()
-        .map(|s| Password::new(s))
+    let passwords = aoc_core::or_exit(Day2::parse(&raw_input), path.as_deref());
+    let mut policies: Vec<&dyn PasswordPolicy> = rulesets.iter()
+        .map(|r| r as &dyn PasswordPolicy)
         .collect();
-
-    let valid_passwords = count_valid_passwords(input);
-
-    println!("Valid Passwords: {}", valid_passwords);
-}
-
-#[derive(Debug)]
-struct PasswordPolicy {
-    min: i32,
-    max: i32,
-    char: char,
-}
-
-impl PasswordPolicy {
-    fn new(policy_str: &str) -> PasswordPolicy {
-        let parts: Vec<&str> = policy_str.split(" ").collect();
-        let minmax = String::from(parts[0]);
-        let char = parts[1].chars().collect::<Vec<char>>()[0];
-
-        let parts: Vec<&str> = minmax.split("-").collect();
-        let min = parts[0].parse::<i32>().unwrap();
-        let max = parts[1].parse::<i32>().unwrap();
-
-        PasswordPolicy { min, max, char }
+    let positional = Positional(Indexing::Char);
+    if policies.is_empty() {
+        policies = vec![&CountRange, &positional];
     }
-}
-
-#[derive(Debug)]
-struct Password {
-    policy: PasswordPolicy,
-    password: String,
-}
 
-impl Password {
-    fn new(line: &str) -> Password {
-        let parts: Vec<&str> = line.split(": ").collect();
-        let policy = PasswordPolicy::new(parts[0]);
-        let password = String::from(parts[1]);
-
-        Password {
-            policy,
-            password
-        }
-    }
-    
-    fn is_valid(&self) -> bool {
-        let mut count = 0;
-        for c in self.password.chars() {
-            if c == self.policy.char {
-                count += 1;
+    match format {
+        Some(format) => println!("{}", Report::new(&passwords, &policies).render(format)),
+        None => {
+            for policy in policies {
+                println!("{}: {} of {} passwords valid",
+                    policy.name(),
+                    count_valid_passwords(&passwords, policy),
+                    passwords.len());
             }
-        }
-        return count >= self.policy.min && count <= self.policy.max
-    }
-
-    fn is_valid_part2(&self) -> bool {
-        println!("Testing password {:?}", self);
-        let chars: Vec<char> = self.password.chars().collect();
-        return ((chars[(self.policy.min-1) as usize] == self.policy.char && chars[(self.policy.max-1) as usize] != self.policy.char)
-            || (chars[(self.policy.min-1) as usize] != self.policy.char && chars[(self.policy.max-1) as usize] == self.policy.char))
+        },
     }
 }
-
-fn count_valid_passwords(input: Vec<Password>) -> usize {
-    input.iter()
-        .map(|p| p.is_valid_part2())
-        .filter(|x| *x)
-        .count()
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_count_valid_passwords_2() {
-        let expected = 2;
-        let raw_input = "\
-1-3 a: abcde
-1-3 b: cdefg
-2-9 c: ccccccccc";
-        let mut input: Vec<Password> = raw_input.split("\n")
-            .map(|s| Password::new(s))
-            .collect();
-
-        assert_eq!(expected, count_valid_passwords(input));
-    }
-
-    #[test]
-    fn test_count_valid_passwords_3() {
-        let expected = 3;
-        let raw_input = "\
-1-3 a: abcde
-1-3 b: cdefg
-2-9 c: ccccccccc
-2-9 z: zzzzzzzzz";
-        let mut input: Vec<Password> = raw_input.split("\n")
-            .map(|s| Password::new(s))
-            .collect();
-
-        assert_eq!(expected, count_valid_passwords(input));
-    }
-}
\ No newline at end of file
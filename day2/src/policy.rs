@@ -0,0 +1,452 @@
+use std::str::FromStr;
+
+use regex::Regex;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::Password;
+
+/// A rule that every password in a database can be audited against.
+pub trait PasswordPolicy {
+    /// The policy as it is written on the command line, e.g. `min-length=8`.
+    fn name(&self) -> String;
+
+    /// Checks the password, explaining why it failed if it did.
+    fn check(&self, password: &Password) -> Result<(), String>;
+
+    fn is_valid(&self, password: &Password) -> bool {
+        self.check(password).is_ok()
+    }
+}
+
+/// Part one's rule: the letter on the line has to appear at least `min` and
+/// at most `max` times.
+pub struct CountRange;
+impl PasswordPolicy for CountRange {
+    fn name(&self) -> String {
+        String::from("count")
+    }
+
+    fn check(&self, password: &Password) -> Result<(), String> {
+        let policy = &password.policy;
+        let mut count = 0;
+        for c in password.password.chars() {
+            if c == policy.char {
+                count += 1;
+            }
+        }
+        if count >= policy.min && count <= policy.max {
+            Ok(())
+        } else {
+            Err(format!("char '{}' appeared {} times, allowed {}-{}", policy.char, count, policy.min, policy.max))
+        }
+    }
+}
+
+/// How positions in a password are counted.  Characters are Unicode scalar
+/// values, so an accented letter written with a combining mark is two
+/// characters but one grapheme.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Indexing {
+    Byte,
+    Char,
+    Grapheme,
+}
+impl Indexing {
+    fn name(self) -> &'static str {
+        match self {
+            Indexing::Byte => "byte",
+            Indexing::Char => "char",
+            Indexing::Grapheme => "grapheme",
+        }
+    }
+
+    // The password split into the units that positions count, as bytes so
+    // that a lone byte of a multi-byte character can still be compared.
+    fn units(self, password: &str) -> Vec<&[u8]> {
+        match self {
+            Indexing::Byte => password.as_bytes().chunks(1).collect(),
+            Indexing::Char => password.char_indices()
+                .map(|(i, c)| &password.as_bytes()[i..i + c.len_utf8()])
+                .collect(),
+            Indexing::Grapheme => password.graphemes(true)
+                .map(str::as_bytes)
+                .collect(),
+        }
+    }
+}
+impl FromStr for Indexing {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Indexing, String> {
+        match s {
+            "byte" => Ok(Indexing::Byte),
+            "char" => Ok(Indexing::Char),
+            "grapheme" => Ok(Indexing::Grapheme),
+            _ => Err(format!("unknown indexing '{}', expected byte, char or grapheme", s)),
+        }
+    }
+}
+
+/// Part two's rule: exactly one of the (1-based) positions `min` and `max`
+/// holds the letter on the line.  A position outside the password makes it
+/// invalid rather than matching nothing.
+pub struct Positional(pub Indexing);
+impl PasswordPolicy for Positional {
+    fn name(&self) -> String {
+        match self.0 {
+            Indexing::Char => String::from("positional"),
+            indexing => format!("positional={}", indexing.name()),
+        }
+    }
+
+    fn check(&self, password: &Password) -> Result<(), String> {
+        log::trace!("Testing password {:?}", password);
+        let policy = &password.policy;
+        let units = self.0.units(&password.password);
+        let mut letter = [0; 4];
+        let letter = policy.char.encode_utf8(&mut letter).as_bytes();
+
+        let holds_letter = |position: i32| -> Result<bool, String> {
+            if position < 1 {
+                return Err(format!("position {} is before the start of the password, positions start at 1", position));
+            }
+            match units.get((position - 1) as usize) {
+                Some(unit) => Ok(*unit == letter),
+                None => Err(format!("position {} is past the end of the {}-{} password",
+                    position, units.len(), self.0.name())),
+            }
+        };
+
+        match (holds_letter(policy.min)?, holds_letter(policy.max)?) {
+            (true, false) | (false, true) => Ok(()),
+            (true, true) => Err(format!("positions {} and {} both contain '{}'", policy.min, policy.max, policy.char)),
+            (false, false) => Err(format!("neither position {} nor {} contains '{}'", policy.min, policy.max, policy.char)),
+        }
+    }
+}
+
+/// The password has at least this many characters.
+pub struct MinLength(pub usize);
+impl PasswordPolicy for MinLength {
+    fn name(&self) -> String {
+        format!("min-length={}", self.0)
+    }
+
+    fn check(&self, password: &Password) -> Result<(), String> {
+        let length = password.password.chars().count();
+        if length >= self.0 {
+            Ok(())
+        } else {
+            Err(format!("length {} is shorter than {}", length, self.0))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CharClass {
+    Lowercase,
+    Uppercase,
+    Digit,
+    Symbol,
+}
+impl CharClass {
+    fn matches(self, c: char) -> bool {
+        match self {
+            CharClass::Lowercase => c.is_lowercase(),
+            CharClass::Uppercase => c.is_uppercase(),
+            CharClass::Digit => c.is_numeric(),
+            CharClass::Symbol => !c.is_alphanumeric() && !c.is_whitespace(),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            CharClass::Lowercase => "lower",
+            CharClass::Uppercase => "upper",
+            CharClass::Digit => "digit",
+            CharClass::Symbol => "symbol",
+        }
+    }
+}
+impl FromStr for CharClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<CharClass, String> {
+        match s {
+            "lower" => Ok(CharClass::Lowercase),
+            "upper" => Ok(CharClass::Uppercase),
+            "digit" => Ok(CharClass::Digit),
+            "symbol" => Ok(CharClass::Symbol),
+            _ => Err(format!("unknown character class '{}', expected lower, upper, digit or symbol", s)),
+        }
+    }
+}
+
+/// The password has at least one character from each of the classes.
+pub struct RequiredClasses(pub Vec<CharClass>);
+impl PasswordPolicy for RequiredClasses {
+    fn name(&self) -> String {
+        let classes: Vec<&str> = self.0.iter().map(|c| c.name()).collect();
+        format!("classes={}", classes.join(","))
+    }
+
+    fn check(&self, password: &Password) -> Result<(), String> {
+        let missing: Vec<&str> = self.0.iter()
+            .filter(|class| !password.password.chars().any(|c| class.matches(c)))
+            .map(|class| class.name())
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("no {} character", missing.join(" or ")))
+        }
+    }
+}
+
+/// The password contains none of the substrings.
+pub struct ForbiddenSubstrings(pub Vec<String>);
+impl PasswordPolicy for ForbiddenSubstrings {
+    fn name(&self) -> String {
+        format!("forbid={}", self.0.join(","))
+    }
+
+    fn check(&self, password: &Password) -> Result<(), String> {
+        match self.0.iter().find(|forbidden| password.password.contains(forbidden.as_str())) {
+            Some(forbidden) => Err(format!("contains forbidden substring '{}'", forbidden)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The password matches the regular expression somewhere.  Anchor the
+/// pattern with `^...$` to match the whole password.
+pub struct RegexMatch(pub Regex);
+impl PasswordPolicy for RegexMatch {
+    fn name(&self) -> String {
+        format!("regex={}", self.0.as_str())
+    }
+
+    fn check(&self, password: &Password) -> Result<(), String> {
+        if self.0.is_match(&password.password) {
+            Ok(())
+        } else {
+            Err(format!("doesn't match /{}/", self.0.as_str()))
+        }
+    }
+}
+
+/// Several policies that all have to pass, built from a spec like
+/// `min-length=8;classes=lower,digit`.
+pub struct Ruleset {
+    policies: Vec<Box<dyn PasswordPolicy>>,
+}
+impl PasswordPolicy for Ruleset {
+    fn name(&self) -> String {
+        let names: Vec<String> = self.policies.iter().map(|p| p.name()).collect();
+        names.join(";")
+    }
+
+    // every failing policy is reported, not just the first
+    fn check(&self, password: &Password) -> Result<(), String> {
+        let reasons: Vec<String> = self.policies.iter()
+            .filter_map(|p| p.check(password).err())
+            .collect();
+        if reasons.is_empty() {
+            Ok(())
+        } else {
+            Err(reasons.join("; "))
+        }
+    }
+}
+impl FromStr for Ruleset {
+    type Err = String;
+
+    // a regex takes the rest of the spec, so its pattern can hold `;`
+    fn from_str(spec: &str) -> Result<Ruleset, String> {
+        let mut policies = Vec::new();
+        let mut rest = Some(spec);
+        while let Some(spec) = rest {
+            let (policy, more) = match spec.split_once(';') {
+                Some((policy, more)) if !spec.starts_with("regex=") => (policy, Some(more)),
+                _ => (spec, None),
+            };
+            policies.push(parse_policy(policy)?);
+            rest = more;
+        }
+        Ok(Ruleset { policies })
+    }
+}
+
+// example input:
+//    count
+//    positional=grapheme
+//    min-length=8
+//    classes=lower,upper,digit
+//    forbid=password,1234
+//    regex=^[a-z]+$
+fn parse_policy(spec: &str) -> Result<Box<dyn PasswordPolicy>, String> {
+    let (kind, arg) = match spec.split_once('=') {
+        Some((kind, arg)) => (kind, Some(arg)),
+        None => (spec, None),
+    };
+    let optional_arg = arg;
+    let arg = || arg.ok_or_else(|| format!("policy '{}' needs a value, like {}=...", kind, kind));
+    let list = |arg: &str| -> Vec<String> {
+        arg.split(',')
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+            .collect()
+    };
+
+    match kind {
+        "count" => Ok(Box::new(CountRange)),
+        "positional" => {
+            let indexing = match optional_arg {
+                Some(indexing) => indexing.parse()?,
+                None => Indexing::Char,
+            };
+            Ok(Box::new(Positional(indexing)))
+        },
+        "min-length" => {
+            let arg = arg()?;
+            let min = arg.parse().map_err(|_| format!("invalid minimum length '{}'", arg))?;
+            Ok(Box::new(MinLength(min)))
+        },
+        "classes" => {
+            let classes = list(arg()?).iter()
+                .map(|c| c.parse())
+                .collect::<Result<Vec<CharClass>, String>>()?;
+            Ok(Box::new(RequiredClasses(classes)))
+        },
+        "forbid" => Ok(Box::new(ForbiddenSubstrings(list(arg()?)))),
+        "regex" => {
+            let pattern = Regex::new(arg()?).map_err(|e| format!("invalid regex: {}", e))?;
+            Ok(Box::new(RegexMatch(pattern)))
+        },
+        _ => Err(format!("unknown policy '{}', expected count, positional, min-length, classes, forbid or regex", kind)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day2;
+    use aoc_core::Solution;
+
+    fn passwords(raw_input: &str) -> Vec<Password> {
+        Day2::parse(raw_input).unwrap()
+    }
+
+    fn valid(ruleset: &str, passwords: &[Password]) -> Vec<bool> {
+        let ruleset: Ruleset = ruleset.parse().unwrap();
+        passwords.iter().map(|p| ruleset.is_valid(p)).collect()
+    }
+
+    #[test]
+    fn test_new_policies() {
+        let input = passwords("\
+1-3 a: abcde
+1-3 b: Password1!
+2-9 c: ccccccccc");
+
+        assert_eq!(vec![false, true, true], valid("min-length=6", &input));
+        assert_eq!(vec![false, true, false], valid("classes=lower,upper,digit,symbol", &input));
+        assert_eq!(vec![true, false, true], valid("forbid=password,Password", &input));
+        assert_eq!(vec![true, false, true], valid("regex=^[a-z]+$", &input));
+    }
+
+    #[test]
+    fn test_ruleset_needs_every_policy() {
+        let input = passwords("\
+1-3 a: abcde
+1-3 b: cdefg
+2-9 c: ccccccccc");
+
+        assert_eq!(vec![true, false, true], valid("count", &input));
+        assert_eq!(vec![true, false, false], valid("count;min-length=2;forbid=cc", &input));
+    }
+
+    #[test]
+    fn test_failure_reasons() {
+        let input = passwords("\
+1-3 a: aaaaa
+1-3 b: bcbde
+1-3 b: cdefg");
+        let reason = |ruleset: &str, password: &Password| {
+            ruleset.parse::<Ruleset>().unwrap().check(password).unwrap_err()
+        };
+
+        assert_eq!("char 'a' appeared 5 times, allowed 1-3", reason("count", &input[0]));
+        assert_eq!("positions 1 and 3 both contain 'b'", reason("positional", &input[1]));
+        assert_eq!("neither position 1 nor 3 contains 'b'", reason("positional", &input[2]));
+        assert_eq!("length 5 is shorter than 8", reason("min-length=8", &input[0]));
+        assert_eq!("no upper or digit character", reason("classes=lower,upper,digit", &input[0]));
+        assert_eq!("contains forbidden substring 'aa'", reason("forbid=zz,aa", &input[0]));
+        assert_eq!("doesn't match /^b/", reason("regex=^b", &input[0]));
+        assert_eq!(
+            "char 'a' appeared 5 times, allowed 1-3; length 5 is shorter than 6",
+            reason("count;min-length=6", &input[0])
+        );
+    }
+
+    #[test]
+    fn test_positional_out_of_range() {
+        let input = passwords("\
+0-3 a: abcde
+1-6 a: abcde
+1-5 e: abcde");
+        let positional = Positional(Indexing::Char);
+
+        assert_eq!(
+            Err(String::from("position 0 is before the start of the password, positions start at 1")),
+            positional.check(&input[0])
+        );
+        assert_eq!(
+            Err(String::from("position 6 is past the end of the 5-char password")),
+            positional.check(&input[1])
+        );
+        assert_eq!(Ok(()), positional.check(&input[2]));
+    }
+
+    #[test]
+    fn test_positional_indexing() {
+        // "e\u{301}" is an e with a combining acute accent: 3 bytes, 2 chars, 1 grapheme
+        let input = passwords("\
+2-3 x: e\u{301}xy
+3-4 x: e\u{301}xy
+4-5 x: e\u{301}xy
+1-2 \u{e9}: \u{e9}a");
+
+        assert_eq!(vec![true, false, false, true], valid("positional=grapheme", &input));
+        assert_eq!(vec![true, true, false, true], valid("positional=char", &input));
+        // a multi-byte letter can never fill a single byte
+        assert_eq!(vec![false, true, true, false], valid("positional=byte", &input));
+        assert_eq!(valid("positional=char", &input), valid("positional", &input));
+    }
+
+    #[test]
+    fn test_ruleset_name_round_trips() {
+        let spec = "count;positional;positional=grapheme;min-length=8;classes=lower,digit;forbid=abc,123;regex=^a";
+        let ruleset: Ruleset = spec.parse().unwrap();
+
+        assert_eq!(spec, ruleset.name());
+    }
+
+    #[test]
+    fn test_ruleset_regex_takes_the_rest() {
+        let input = passwords("1-3 a: a;b\n1-3 a: ab");
+
+        assert_eq!(vec![true, false], valid("count;regex=^a;b$", &input));
+        assert_eq!("count;regex=^a;b$", "count;regex=^a;b$".parse::<Ruleset>().unwrap().name());
+    }
+
+    #[test]
+    fn test_ruleset_rejects_bad_specs() {
+        assert!("length=8".parse::<Ruleset>().is_err());
+        assert!("min-length".parse::<Ruleset>().is_err());
+        assert!("min-length=eight".parse::<Ruleset>().is_err());
+        assert!("classes=lower,emoji".parse::<Ruleset>().is_err());
+        assert!("regex=(".parse::<Ruleset>().is_err());
+        assert!("positional=word".parse::<Ruleset>().is_err());
+    }
+}
@@ -0,0 +1,116 @@
+use std::fmt;
+
+use aoc_core::report::Render;
+use serde::Serialize;
+
+use crate::policy::PasswordPolicy;
+use crate::Password;
+
+/// The outcome of checking one line of the database against one policy.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ReportEntry {
+    pub line: usize,
+    pub policy: String,
+    // the policy written on the line itself, e.g. `1-3 a`
+    pub rule: String,
+    pub password: String,
+    pub valid: bool,
+    pub reason: Option<String>,
+}
+
+/// A line-by-line audit of a password database, one entry for each password
+/// and policy.
+#[derive(Debug)]
+pub struct Report {
+    pub entries: Vec<ReportEntry>,
+}
+impl Report {
+    /// Checks every password against every policy, in database order.
+    pub fn new(passwords: &[Password], policies: &[&dyn PasswordPolicy]) -> Report {
+        let mut entries = Vec::new();
+        for password in passwords {
+            for policy in policies {
+                let result = policy.check(password);
+                entries.push(ReportEntry {
+                    line: password.line,
+                    policy: policy.name(),
+                    rule: password.policy.to_string(),
+                    password: password.password.clone(),
+                    valid: result.is_ok(),
+                    reason: result.err(),
+                });
+            }
+        }
+        Report { entries }
+    }
+}
+impl Render for Report {
+    fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.entries).expect("report entries always serialize")
+    }
+}
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in self.entries.iter() {
+            write!(f, "line {}: {} ({}) {} {}", entry.line, entry.password, entry.rule,
+                if entry.valid { "passes" } else { "fails" }, entry.policy)?;
+            match &entry.reason {
+                Some(reason) => writeln!(f, ": {}", reason)?,
+                None => writeln!(f)?,
+            }
+        }
+        let valid = self.entries.iter().filter(|e| e.valid).count();
+        write!(f, "{} of {} checks passed", valid, self.entries.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::{CountRange, Ruleset};
+    use crate::Day2;
+    use aoc_core::Solution;
+
+    #[test]
+    fn test_report_text() {
+        let passwords = Day2::parse("\
+1-3 a: abcde
+1-3 b: cdefg").unwrap();
+        let expected = "\
+line 1: abcde (1-3 a) passes count
+line 2: cdefg (1-3 b) fails count: char 'b' appeared 0 times, allowed 1-3
+1 of 2 checks passed";
+
+        let report = Report::new(&passwords, &[&CountRange]);
+
+        assert_eq!(expected, report.to_string());
+    }
+
+    #[test]
+    fn test_report_json() {
+        let passwords = Day2::parse("1-3 a: abcde").unwrap();
+        let ruleset: Ruleset = "min-length=8".parse().unwrap();
+        let expected = r#"[
+  {
+    "line": 1,
+    "policy": "count",
+    "rule": "1-3 a",
+    "password": "abcde",
+    "valid": true,
+    "reason": null
+  },
+  {
+    "line": 1,
+    "policy": "min-length=8",
+    "rule": "1-3 a",
+    "password": "abcde",
+    "valid": false,
+    "reason": "length 5 is shorter than 8"
+  }
+]"#;
+
+        let report = Report::new(&passwords, &[&CountRange, &ruleset]);
+
+        assert_eq!(expected, report.to_json());
+    }
+}
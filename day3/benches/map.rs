@@ -0,0 +1,73 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use day3::legend::Legend;
+use day3::{solve_part_1, Map};
+
+// The layout `Map` used before it was packed: a `Vec` of legend indexes per
+// row, with the width looked up again on every access.
+struct NestedMap {
+    grid: Vec<Vec<usize>>,
+    legend: Legend,
+}
+impl NestedMap {
+    fn new(str_map: &str, legend: Legend) -> NestedMap {
+        NestedMap {
+            grid: str_map.lines()
+                .map(|row| row.chars()
+                    .map(|c| legend.tiles().iter().position(|tile| tile.symbol == c).unwrap())
+                    .collect())
+                .collect(),
+            legend,
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.grid[0].len()
+    }
+
+    fn cost(&self, right: usize, down: usize) -> u64 {
+        let (mut x, mut y, mut cost) = (0, 0, 0);
+        loop {
+            x += right;
+            y += down;
+            if y >= self.grid.len() {
+                return cost;
+            }
+            cost += u64::from(self.legend.tiles()[self.grid[y][x % self.width()]].cost);
+        }
+    }
+}
+
+// A repeatable pseudo-random forest, about one square in four a tree.
+fn forest(height: usize, width: usize) -> String {
+    let mut seed: u64 = 2020;
+    let mut raw = String::with_capacity(height * (width + 1));
+    for _ in 0..height {
+        for _ in 0..width {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            raw.push(if seed >> 62 == 0 { '#' } else { '.' });
+        }
+        raw.push('\n');
+    }
+    raw
+}
+
+fn slopes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("slopes");
+    group.sample_size(10);
+    for &height in &[10_000, 1_000_000] {
+        let raw = forest(height, 31);
+        let packed = Map::with_legend(&raw, Legend::default()).unwrap();
+        let nested = NestedMap::new(&raw, Legend::default());
+
+        group.bench_with_input(BenchmarkId::new("packed", height), &packed, |b, map| {
+            b.iter(|| (1..=50).map(|right| solve_part_1(map, black_box(right), 1)).sum::<u64>())
+        });
+        group.bench_with_input(BenchmarkId::new("nested", height), &nested, |b, map| {
+            b.iter(|| (1..=50).map(|right| map.cost(black_box(right), 1)).sum::<u64>())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, slopes);
+criterion_main!(benches);
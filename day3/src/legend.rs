@@ -0,0 +1,118 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::Space;
+
+/// A map symbol, the terrain it stands for and what landing on it costs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tile {
+    pub symbol: char,
+    pub space: Space,
+    pub cost: u32,
+}
+impl FromStr for Tile {
+    type Err = String;
+
+    // example tile: o=rock:5
+    fn from_str(s: &str) -> Result<Tile, String> {
+        let (symbol, terrain) = s.split_once('=')
+            .ok_or_else(|| format!("expected a tile like `o=rock:5`, found '{}'", s))?;
+        let mut symbols = symbol.chars();
+        let symbol = match (symbols.next(), symbols.next()) {
+            (Some(symbol), None) => symbol,
+            _ => return Err(format!("expected a single character symbol, found '{}'", symbol)),
+        };
+        let (space, cost) = terrain.split_once(':')
+            .ok_or_else(|| format!("expected a terrain and cost like `rock:5`, found '{}'", terrain))?;
+        let cost = cost.parse()
+            .map_err(|_| format!("invalid cost '{}'", cost))?;
+
+        Ok(Tile {
+            symbol,
+            space: space.parse()?,
+            cost,
+        })
+    }
+}
+impl fmt::Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}:{}", self.symbol, self.space, self.cost)
+    }
+}
+
+/// Which symbols a map can contain.  The default legend is the puzzle's:
+/// `.` is open and free, `#` is a tree and costs 1, so the cost of a run is
+/// the number of trees it hits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Legend {
+    tiles: Vec<Tile>,
+}
+impl Legend {
+    /// Adds `tile` to the legend, replacing any tile with the same symbol.
+    pub fn insert(&mut self, tile: Tile) {
+        match self.index_of(tile.symbol) {
+            Some(i) => self.tiles[i] = tile,
+            None => self.tiles.push(tile),
+        }
+    }
+
+    pub fn tiles(&self) -> &[Tile] {
+        &self.tiles
+    }
+
+    pub(crate) fn index_of(&self, symbol: char) -> Option<usize> {
+        self.tiles.iter().position(|tile| tile.symbol == symbol)
+    }
+}
+impl Default for Legend {
+    fn default() -> Legend {
+        Legend {
+            tiles: vec![
+                Tile { symbol: '.', space: Space::OPEN, cost: 0 },
+                Tile { symbol: '#', space: Space::TREE, cost: 1 },
+            ],
+        }
+    }
+}
+impl FromStr for Legend {
+    type Err = String;
+
+    /// Parses tiles separated by `,` on top of the default legend, e.g.
+    /// `o=rock:5,~=ice:0,#=tree:3`.
+    fn from_str(s: &str) -> Result<Legend, String> {
+        let mut legend = Legend::default();
+        for tile in s.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            legend.insert(tile.parse()?);
+        }
+        Ok(legend)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_legend() {
+        let legend: Legend = "o=rock:5, ~=ice:0,#=tree:3".parse().unwrap();
+        let expected = vec![
+            Tile { symbol: '.', space: Space::OPEN, cost: 0 },
+            Tile { symbol: '#', space: Space::TREE, cost: 3 },
+            Tile { symbol: 'o', space: Space::ROCK, cost: 5 },
+            Tile { symbol: '~', space: Space::ICE, cost: 0 },
+        ];
+
+        assert_eq!(expected, legend.tiles());
+        assert_eq!(Ok(Legend::default()), "".parse());
+    }
+
+    #[test]
+    fn test_parse_tile_errors() {
+        assert_eq!(Err(String::from("unknown terrain 'lava', expected open, tree, rock, ice or snow")),
+            "x=lava:1".parse::<Tile>());
+        assert!("oo=rock:5".parse::<Tile>().is_err());
+        assert!("o=rock".parse::<Tile>().is_err());
+        assert!("o=rock:-1".parse::<Tile>().is_err());
+        assert_eq!("*=snow:2", "*=snow:2".parse::<Tile>().unwrap().to_string());
+    }
+}
@@ -0,0 +1,697 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use aoc_core::{column_of, ParseError, Solution};
+
+use legend::{Legend, Tile};
+
+pub mod legend;
+pub mod route;
+
+pub struct Day3;
+impl Solution for Day3 {
+    type Input = Map;
+    type Part1 = u64;
+    type Part2 = u128;
+
+    fn parse(raw_input: &str) -> Result<Map, ParseError> {
+        Map::new(raw_input)
+    }
+
+    fn part1(input: &Map) -> u64 {
+        solve_part_1(input, 3, 1)
+    }
+
+    fn part2(input: &Map) -> u128 {
+        solve_part_2(input, &PUZZLE_SLOPES)
+    }
+}
+
+/// The slopes that part two multiplies together.
+pub const PUZZLE_SLOPES: [Slope; 5] = [
+    Slope { right: 1, down: 1 },
+    Slope { right: 3, down: 1 },
+    Slope { right: 5, down: 1 },
+    Slope { right: 7, down: 1 },
+    Slope { right: 1, down: 2 },
+];
+
+/// How far the toboggan moves on each step, written `right,down` (e.g.
+/// `3,1`).  It always has to move down or it would never reach the bottom.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Slope {
+    pub right: usize,
+    pub down: usize,
+}
+impl FromStr for Slope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Slope, String> {
+        let (right, down) = s.split_once(',')
+            .ok_or_else(|| format!("expected a slope like `3,1`, found '{}'", s))?;
+        let right = right.trim().parse()
+            .map_err(|_| format!("invalid distance right '{}'", right))?;
+        let down = down.trim().parse()
+            .map_err(|_| format!("invalid distance down '{}'", down))?;
+        if down == 0 {
+            return Err(String::from("a slope has to move down at least 1"));
+        }
+        Ok(Slope { right, down })
+    }
+}
+impl fmt::Display for Slope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.right, self.down)
+    }
+}
+
+/// Reads a slopes file: one `right,down` slope per line, ignoring blank
+/// lines and `#` comments.
+pub fn parse_slopes(raw_input: &str) -> Result<Vec<Slope>, ParseError> {
+    let mut slopes = Vec::new();
+    for (i, line) in raw_input.lines().enumerate() {
+        let slope = line.split('#').next().unwrap_or("").trim();
+        if slope.is_empty() {
+            continue;
+        }
+        slopes.push(slope.parse()
+            .map_err(|e: String| ParseError::new(i + 1, column_of(line, slope), slope, &e))?);
+    }
+    Ok(slopes)
+}
+
+/// One stop on the way down the slope: where the toboggan landed, what was
+/// there and what it cost.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Step {
+    pub at: Coordinate,
+    pub space: Space,
+    pub cost: u32,
+}
+
+/// Every position visited following `slope` from the top-left corner until
+/// the toboggan runs off the bottom of the map.  The starting corner isn't
+/// included, just like it isn't counted.  `x` keeps growing past the edge of
+/// the map rather than wrapping, so the path can be drawn over the repeated
+/// map.  Panics if the slope doesn't move down.
+pub fn trajectory(map: &Map, slope: Slope) -> Vec<Step> {
+    Run::new(map, slope).collect()
+}
+
+/// The total cost of the run down one slope.  With the default legend that's
+/// the number of trees hit.  Panics if `vertical` is 0, since the run would
+/// never reach the bottom.
+pub fn solve_part_1(map: &Map, horizontal: usize, vertical: usize) -> u64 {
+    assert!(vertical > 0, "a slope has to move down at least 1");
+    // the same walk as `Run`, without building a `Step` for every square
+    let costs: Vec<u64> = map.legend.tiles().iter().map(|tile| u64::from(tile.cost)).collect();
+    let mut column = 0;
+    let mut cost = 0;
+    for y in (vertical..map.height).step_by(vertical) {
+        column += horizontal;
+        if column >= map.width {
+            column %= map.width;
+        }
+        cost += costs[map.index(column, y)];
+    }
+    cost
+}
+
+// Walks down a slope one step at a time.  The column on the map is wrapped
+// as it goes, so it only needs a division when it runs off the edge.
+struct Run<'a> {
+    map: &'a Map,
+    slope: Slope,
+    position: Coordinate,
+    column: usize,
+}
+impl<'a> Run<'a> {
+    fn new(map: &'a Map, slope: Slope) -> Run<'a> {
+        assert!(slope.down > 0, "a slope has to move down at least 1");
+        Run {
+            map,
+            slope,
+            position: Coordinate { x: 0, y: 0 },
+            column: 0,
+        }
+    }
+}
+impl Iterator for Run<'_> {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Step> {
+        //move
+        self.position = Coordinate {
+            x: self.position.x + self.slope.right,
+            y: self.position.y + self.slope.down,
+        };
+        self.column += self.slope.right;
+        if self.column >= self.map.width {
+            self.column %= self.map.width;
+        }
+
+        // check if we're done
+        if self.position.y >= self.map.height {
+            return None;
+        }
+
+        let tile = self.map.tile(self.column, self.position.y);
+        Some(Step {
+            at: self.position,
+            space: tile.space,
+            cost: tile.cost,
+        })
+    }
+}
+
+/// Multiplies together the costs (trees hit, by default) of each slope.  A
+/// product too large for a `u128`, which takes very costly tiles, stops at
+/// `u128::MAX`.
+pub fn solve_part_2(map: &Map, slopes: &[Slope]) -> u128 {
+    let answers: Vec<u128> = slopes.iter()
+        .map(|slope| u128::from(solve_part_1(map, slope.right, slope.down)))
+        .collect();
+    let final_answer = answers.iter().fold(1, |product: u128, answer| product.saturating_mul(*answer));
+    log::info!("part 2: {} = {}",
+            answers.iter().map(|a| a.to_string()).collect::<Vec<String>>().join(" * "),
+            final_answer);
+    
+    final_answer
+}
+
+/// The slopes with the lowest and the highest cost (the fewest and the most
+/// trees, by default) out of every slope within the search bounds.
+#[derive(Debug, PartialEq)]
+pub struct SlopeSearch {
+    pub fewest: (Slope, u64),
+    pub most: (Slope, u64),
+}
+
+/// Tries every slope moving `0..=max.right` right and `1..=max.down` down.
+/// Ties go to the slope tried first, the one with the smallest moves.
+pub fn search_slopes(map: &Map, max: Slope) -> SlopeSearch {
+    let first = Slope { right: 0, down: 1 };
+    let trees = solve_part_1(map, first.right, first.down);
+    let mut search = SlopeSearch {
+        fewest: (first, trees),
+        most: (first, trees),
+    };
+
+    for down in 1..=max.down {
+        for right in 0..=max.right {
+            let slope = Slope { right, down };
+            let trees = solve_part_1(map, right, down);
+            log::debug!("slope {} costs {}", slope, trees);
+            if trees < search.fewest.1 {
+                search.fewest = (slope, trees);
+            }
+            if trees > search.most.1 {
+                search.most = (slope, trees);
+            }
+        }
+    }
+    search
+}
+
+#[derive(Debug, Clone, Copy)]
+#[derive(PartialEq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum Space {
+    OPEN,
+    TREE,
+    ROCK,
+    ICE,
+    SNOW,
+}
+impl FromStr for Space {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Space, String> {
+        match s {
+            "open" => Ok(Space::OPEN),
+            "tree" => Ok(Space::TREE),
+            "rock" => Ok(Space::ROCK),
+            "ice" => Ok(Space::ICE),
+            "snow" => Ok(Space::SNOW),
+            _ => Err(format!("unknown terrain '{}', expected open, tree, rock, ice or snow", s)),
+        }
+    }
+}
+impl fmt::Display for Space {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Space::OPEN => write!(f, "open"),
+            Space::TREE => write!(f, "tree"),
+            Space::ROCK => write!(f, "rock"),
+            Space::ICE => write!(f, "ice"),
+            Space::SNOW => write!(f, "snow"),
+        }
+    }
+}
+
+/// How [`PathView`] draws the path: plain characters, or with ANSI colours
+/// for a terminal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Style {
+    Plain,
+    Ansi,
+}
+impl FromStr for Style {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Style, String> {
+        match s {
+            "plain" => Ok(Style::Plain),
+            "ansi" => Ok(Style::Ansi),
+            _ => Err(format!("unknown style '{}', expected plain or ansi", s)),
+        }
+    }
+}
+
+#[derive(Debug)]
+#[derive(PartialEq)]
+struct Size {
+    height: usize,
+    width: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct Coordinate {
+    pub x: usize,
+    pub y: usize,
+}
+
+/// The map, packed as tightly as the legend allows: each square is its index
+/// into the legend's tiles, one bit apiece for the default `.`/`#` legend,
+/// and each row starts on a fresh word.  Squares take a power of two bits so
+/// finding one is all shifts and masks.
+#[derive(Debug)]
+pub struct Map {
+    cells: Vec<u64>,
+    height: usize,
+    width: usize,
+    // log2 of the bits per square and of the squares per word, and words per row
+    bits_shift: usize,
+    word_shift: usize,
+    stride: usize,
+    legend: Legend,
+}
+impl Map {
+    fn new(str_map: &str) -> Result<Map, ParseError> {
+        Map::with_legend(str_map, Legend::default())
+    }
+
+    /// Reads a map whose symbols are given by `legend`.
+    pub fn with_legend(str_map: &str, legend: Legend) -> Result<Map, ParseError> {
+        let bits = (usize::BITS - legend.tiles().len().saturating_sub(1).leading_zeros()).max(1);
+        let bits_shift = bits.next_power_of_two().trailing_zeros() as usize;
+        let mut map = Map {
+            cells: Vec::new(),
+            height: 0,
+            width: 0,
+            bits_shift,
+            word_shift: 6 - bits_shift,
+            stride: 0,
+            legend,
+        };
+        
+        for (y, row) in str_map.lines().enumerate() {
+            // populate the map's row
+            let mut row_spaces = Vec::new();
+            for (x, symbol) in row.char_indices() {
+                match map.legend.index_of(symbol) {
+                    Some(tile) => row_spaces.push(tile as u64),
+                    None => return Err(ParseError::new(y + 1, x + 1, &row[x..x + symbol.len_utf8()], "unknown map symbol")),
+                }
+            }
+
+            // every row has to be as wide as the first or the wrap-around breaks
+            if map.height > 0 {
+                if map.width != row_spaces.len() {
+                    return Err(ParseError::new(y + 1, 1, row, &format!("expected a row {} spaces wide", map.width)));
+                }
+            } else if row_spaces.is_empty() {
+                return Err(ParseError::new(y + 1, 1, row, "expected a row of map symbols"));
+            } else {
+                map.width = row_spaces.len();
+                map.stride = map.width.div_ceil(1 << map.word_shift);
+            }
+
+            for word in row_spaces.chunks(1 << map.word_shift) {
+                map.cells.push(word.iter()
+                    .enumerate()
+                    .fold(0, |packed, (i, tile)| packed | tile << (i << map.bits_shift)));
+            }
+            map.height += 1;
+        }
+
+        if map.height == 0 {
+            return Err(ParseError::new(1, 1, str_map, "expected at least one row of map symbols"));
+        }
+        
+        Ok(map)
+    }
+    
+    // returns (height, width)
+    fn size(&self) -> Size {
+        Size {
+            height: self.height,
+            width: self.width,
+        }
+    }
+    
+    fn get(&self, x: usize, y: usize) -> &Tile {
+        self.tile(x % self.width, y)
+    }
+
+    // like `get`, but `column` has to be on the map already
+    fn tile(&self, column: usize, y: usize) -> &Tile {
+        &self.legend.tiles()[self.index(column, y)]
+    }
+
+    // the legend index of the square at `column`, which has to be on the map
+    fn index(&self, column: usize, y: usize) -> usize {
+        let word = self.cells[y * self.stride + (column >> self.word_shift)];
+        let offset = (column & ((1 << self.word_shift) - 1)) << self.bits_shift;
+        let mask = (1 << (1 << self.bits_shift)) - 1;
+        ((word >> offset) & mask) as usize
+    }
+
+    /// Draws `path` over the map the way the puzzle does, marking squares
+    /// that cost something (trees, by default) with `X` and free ones with
+    /// `O`.
+    pub fn with_path<'a>(&'a self, path: &'a [Step], style: Style) -> PathView<'a> {
+        PathView {
+            map: self,
+            path,
+            style,
+        }
+    }
+
+    // Writes the map, repeated to the right as often as it takes to reach
+    // the furthest step of `path`.
+    fn write_with_path(&self, f: &mut fmt::Formatter<'_>, path: &[Step], style: Style) -> fmt::Result {
+        let width = self.size().width;
+        let furthest = path.iter().map(|step| step.at.x).max().unwrap_or(0);
+        let repeats = furthest / width + 1;
+        let marks: HashMap<Coordinate, bool> = path.iter()
+            .map(|step| (step.at, step.cost > 0))
+            .collect();
+
+        for y in 0..self.size().height {
+            for x in 0..width * repeats {
+                match (marks.get(&Coordinate { x, y }), style) {
+                    (None, _) => write!(f, "{}", self.get(x, y).symbol)?,
+                    (Some(true), Style::Plain) => write!(f, "X")?,
+                    (Some(false), Style::Plain) => write!(f, "O")?,
+                    (Some(true), Style::Ansi) => write!(f, "\x1b[1;31mX\x1b[0m")?,
+                    (Some(false), Style::Ansi) => write!(f, "\x1b[1;32mO\x1b[0m")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+impl fmt::Display for Map {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_with_path(f, &[], Style::Plain)
+    }
+}
+
+/// A map with a toboggan run drawn over it, see [`Map::with_path`].
+pub struct PathView<'a> {
+    map: &'a Map,
+    path: &'a [Step],
+    style: Style,
+}
+impl fmt::Display for PathView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.map.write_with_path(f, self.path, self.style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_part_1_a() {
+        let expected = 7;
+        let raw_input = "\
+..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#";
+        let input = Map::new(raw_input).unwrap();
+
+        assert_eq!(expected, solve_part_1(&input, 3, 1));
+    }
+
+    #[test]
+    fn test_map_size() {
+        let expected = Size { height: 11, width: 11 };
+        let raw_input = "\
+..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#";
+        let map = Map::new(raw_input).unwrap();
+
+        assert_eq!(expected, map.size());
+    }
+
+    #[test]
+    fn test_map_index() {
+        let expected = Space::OPEN;
+        let raw_input = "\
+..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#";
+        let map = Map::new(raw_input).unwrap();
+        println!("{}", map);
+        
+        assert_eq!(expected, map.get(1, 9).space);
+    }
+
+    #[test]
+    fn test_map_unknown_symbol() {
+        let raw_input = "\
+..##...
+#..x#..";
+        let expected = ParseError::new(2, 4, "x", "unknown map symbol");
+
+        assert_eq!(expected, Map::new(raw_input).unwrap_err());
+    }
+
+    #[test]
+    fn test_map_ragged_row() {
+        let raw_input = "\
+..##...
+#...";
+        let expected = ParseError::new(2, 1, "#...", "expected a row 7 spaces wide");
+
+        assert_eq!(expected, Map::new(raw_input).unwrap_err());
+    }
+
+    #[test]
+    fn test_solve_part_2_puzzle_slopes() {
+        let raw_input = "\
+..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#";
+        let map = Map::new(raw_input).unwrap();
+
+        assert_eq!(336, solve_part_2(&map, &PUZZLE_SLOPES));
+        assert_eq!(7 * 2, solve_part_2(&map, &[Slope { right: 3, down: 1 }, Slope { right: 1, down: 1 }]));
+    }
+
+    #[test]
+    fn test_parse_slopes() {
+        let raw_input = "\
+# right,down
+3,1
+ 1, 2  # every other row
+
+7,1";
+        let expected = vec![
+            Slope { right: 3, down: 1 },
+            Slope { right: 1, down: 2 },
+            Slope { right: 7, down: 1 },
+        ];
+
+        assert_eq!(Ok(expected), parse_slopes(raw_input));
+    }
+
+    #[test]
+    fn test_parse_slopes_rejects_bad_slope() {
+        let expected = ParseError::new(2, 1, "3,0", "a slope has to move down at least 1");
+
+        assert_eq!(Err(expected), parse_slopes("3,1\n3,0"));
+        assert!("3".parse::<Slope>().is_err());
+        assert!("a,1".parse::<Slope>().is_err());
+    }
+
+    #[test]
+    fn test_search_slopes() {
+        let raw_input = "\
+..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#";
+        let map = Map::new(raw_input).unwrap();
+        let expected = SlopeSearch {
+            fewest: (Slope { right: 2, down: 1 }, 1),
+            most: (Slope { right: 3, down: 1 }, 7),
+        };
+
+        assert_eq!(expected, search_slopes(&map, Slope { right: 3, down: 2 }));
+    }
+
+    #[test]
+    fn test_trajectory() {
+        let raw_input = "\
+..##.......
+#...#...#..
+.#....#..#.";
+        let map = Map::new(raw_input).unwrap();
+        let expected = vec![
+            Step { at: Coordinate { x: 3, y: 1 }, space: Space::OPEN, cost: 0 },
+            Step { at: Coordinate { x: 6, y: 2 }, space: Space::TREE, cost: 1 },
+        ];
+
+        assert_eq!(expected, trajectory(&map, Slope { right: 3, down: 1 }));
+        assert!(trajectory(&map, Slope { right: 1, down: 3 }).is_empty());
+    }
+
+    #[test]
+    fn test_render_path() {
+        let raw_input = "\
+..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#";
+        let expected = "\
+..##.........##.........##.......
+#..O#...#..#...#...#..#...#...#..
+.#....X..#..#....#..#..#....#..#.
+..#.#...#O#..#.#...#.#..#.#...#.#
+.#...##..#..X...##..#..#...##..#.
+..#.##.......#.X#.......#.##.....
+.#.#.#....#.#.#.#.O..#.#.#.#....#
+.#........#.#........X.#........#
+#.##...#...#.##...#...#.X#...#...
+#...##....##...##....##...#X....#
+.#..#...#.#.#..#...#.#.#..#...X.#
+";
+        let map = Map::new(raw_input).unwrap();
+        let path = trajectory(&map, Slope { right: 3, down: 1 });
+
+        assert_eq!(expected, map.with_path(&path, Style::Plain).to_string());
+        assert_eq!(raw_input, map.to_string().trim_end());
+    }
+
+    #[test]
+    fn test_render_path_ansi() {
+        let map = Map::new("..\n..\n#.").unwrap();
+        let path = trajectory(&map, Slope { right: 1, down: 1 });
+        let expected = "....\n.\x1b[1;32mO\x1b[0m..\n#.\x1b[1;31mX\x1b[0m.\n";
+
+        assert_eq!(expected, map.with_path(&path, Style::Ansi).to_string());
+        assert!("sepia".parse::<Style>().is_err());
+    }
+
+    #[test]
+    fn test_weighted_cost() {
+        let raw_input = "\
+..o~.
+#...*
+.#o.~
+~.*#.";
+        let legend: Legend = "o=rock:5,~=ice:0,*=snow:2".parse().unwrap();
+        let map = Map::with_legend(raw_input, legend).unwrap();
+        let expected = vec![Space::OPEN, Space::ROCK, Space::TREE];
+
+        // (1,1) is open, (2,2) a rock, (3,3) a tree
+        assert_eq!(expected, trajectory(&map, Slope { right: 1, down: 1 }).iter().map(|s| s.space).collect::<Vec<Space>>());
+        assert_eq!(6, solve_part_1(&map, 1, 1));
+        assert_eq!(raw_input, map.to_string().trim_end());
+        assert_eq!(Err(ParseError::new(1, 3, "o", "unknown map symbol")), Map::new(raw_input).map(|_| ()));
+    }
+
+    #[test]
+    #[should_panic(expected = "a slope has to move down at least 1")]
+    fn test_solve_part_1_flat_slope() {
+        solve_part_1(&Map::new("..\n.#").unwrap(), 1, 0);
+    }
+
+    #[test]
+    fn test_weighted_cost_past_u32() {
+        let raw_input = "o\no\no";
+        let legend: Legend = "o=rock:4294967295".parse().unwrap();
+        let map = Map::with_legend(raw_input, legend).unwrap();
+
+        assert_eq!(2 * u64::from(u32::MAX), solve_part_1(&map, 0, 1));
+        assert_eq!(u128::MAX, solve_part_2(&map, &[Slope { right: 0, down: 1 }; 5]));
+        assert_eq!(Some(2 * u64::from(u32::MAX)), route::safest_route(&map, &route::DEFAULT_MOVES).map(|r| r.cost));
+    }
+
+    #[test]
+    fn test_packed_rows() {
+        // 5 tiles take 4 bits a square, so 70 squares spill over five words
+        let raw_input = format!("{}\n{}", "#.".repeat(35), "o*~".repeat(23) + "#");
+        let legend: Legend = "o=rock:5,~=ice:0,*=snow:2".parse().unwrap();
+        let map = Map::with_legend(&raw_input, legend).unwrap();
+
+        assert_eq!(Size { height: 2, width: 70 }, map.size());
+        assert_eq!(raw_input, map.to_string().trim_end());
+        assert_eq!(Space::TREE, map.get(68, 0).space);
+        assert_eq!(Space::TREE, map.get(139, 1).space);
+        assert_eq!(Space::ROCK, map.get(140, 1).space);
+    }
+}
@@ -1,213 +1,100 @@
-use std::env;
-use std::fs;
-use std::fmt;
+use aoc_core::cli::{self, Args};
+use aoc_core::{logging, Solution};
+use day3::legend::Legend;
+use day3::route::{safest_route, Move, DEFAULT_MOVES};
+use day3::{parse_slopes, search_slopes, solve_part_2, trajectory, Day3, Map, Slope, Style};
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    let raw_input = fs::read_to_string(&args[1])
-        .expect("Something went wrong reading the file");
-    let input = Map::new(raw_input.as_str());
-    
-    //TODO: implement the solver function
-    println!("Solution Part 1:  {:?}", solve_part_1(&input, 3, 1));
-    println!("Solution Part 2:  {:?}", solve_part_2(&input));
-}
+fn usage() -> String {
+    format!("\
+Usage: day3 [--slope <R,D>]... [--slopes <FILE>] [--search <R,D>] [--render <STYLE>]
+            [--legend <TILES>] [--route] [--move <R,D>]... [-v|-q] [INPUT]
 
-fn parse(raw_input: &str) {
-    raw_input.lines()
-        .map(|s| s)
-        .collect::<Vec<&str>>();
-}
+Without any options, prints the answers to both parts of the puzzle.
 
-fn solve_part_1(map: &Map, horizontal: usize, vertical: usize) -> i32 {
-    let mut trees = 0;
-    let mut position = Coordinate { x: 0, y: 0 };
-    loop {
-        //move
-        position = Coordinate {
-            x: position.x + horizontal,
-            y: position.y + vertical,
-        };
-        
-        // check if we're done
-        if position.y >= map.size().height {
-            break;
-        } 
-        
-        // check if we hit a tree
-        match map.get(position.x, position.y) {
-            Space::OPEN => {},
-            Space::TREE => trees += 1,
-        }
-    }
-    trees
-}
+Options:
+    --slope <R,D>      count the trees hit (or the cost, with --legend) moving
+                       R right and D down each step, and multiply the counts
+                       together; can be given more than once
+    --slopes <FILE>    read more slopes from FILE, one `R,D` per line, with
+                       `#` comments
+    --search <R,D>     try every slope moving up to R right and D down and
+                       print the ones that hit the fewest and the most trees
+    --render <STYLE>   draw the path of each slope (3,1 if none are given
+                       and there's no --route) and the route over the map,
+                       marking trees hit with X and open squares with O, as
+                       plain text or ansi colours
+    --legend <TILES>   add map symbols, given as `SYMBOL=TERRAIN:COST`
+                       separated by `,`, where TERRAIN is open, tree, rock,
+                       ice or snow; the default legend is `.=open:0,#=tree:1`
+                       and a run costs the sum of the squares it lands on.
+                       With no other options, prints the answers to both
+                       parts as costs
+    --route            find the route down from any square on the top row
+                       that hits the fewest trees (or costs the least, with
+                       --legend), moving down, down-left or down-right
+    --move <R,D>       allow the route to move R right (negative for left)
+                       and D down, instead of the default moves; can be
+                       given more than once, and implies --route
+{}
 
-fn solve_part_2(map: &Map) -> u128 {
-    let answer1: u128 = solve_part_1(map, 1, 1) as u128;
-    let answer2: u128 =  solve_part_1(map, 3, 1) as u128;
-    let answer3: u128 = solve_part_1(map, 5, 1) as u128;
-    let answer4: u128 = solve_part_1(map, 7, 1) as u128;
-    let answer5: u128 = solve_part_1(map, 1, 2) as u128;
-    let final_answer = answer1 * answer2 * answer3 * answer4 * answer5;
-    println!("part 2: {} * {} * {} * {} * {} = {}",
-            answer1,
-            answer2,
-            answer3,
-            answer4,
-            answer5, 
-            final_answer);
-    
-    final_answer
+Reads the map from INPUT, or from stdin when INPUT is omitted or `-`.", logging::USAGE)
 }
 
-#[derive(Debug)]
-#[derive(PartialEq)]
-enum Space {
-    OPEN,
-    TREE,
-}
-impl Space {
-    fn from(c: char) -> Space {
-        match c {
-            '.' => Space::OPEN,
-            '#' => Space::TREE,
-            _ => panic!("Unknown map symbol!"),
-        }
+fn main() {
+    let usage = usage();
+    let mut args = Args::from_env();
+    logging::init_from_args(&mut args);
+    let mut slopes: Vec<Slope> = cli::or_usage(args.values("--slope"), &usage);
+    let slopes_file: Option<String> = cli::or_usage(args.value("--slopes"), &usage);
+    let search: Option<Slope> = cli::or_usage(args.value("--search"), &usage);
+    let render: Option<Style> = cli::or_usage(args.value("--render"), &usage);
+    let legend: Option<Legend> = cli::or_usage(args.value("--legend"), &usage);
+    let mut moves: Vec<Move> = cli::or_usage(args.values("--move"), &usage);
+    let route = args.flag(&["--route"]) || !moves.is_empty();
+    if moves.is_empty() {
+        moves = DEFAULT_MOVES.to_vec();
     }
-}
-impl fmt::Display for Space {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Space::OPEN => write!(f, "."),
-            Space::TREE => write!(f, "#"),
+    let path = cli::or_usage(args.finish(), &usage);
+
+    if let Some(file) = slopes_file.as_deref() {
+        let raw_slopes = aoc_core::load_input(Some(file));
+        slopes.extend(aoc_core::or_exit(parse_slopes(&raw_slopes), Some(file)));
+        if slopes.is_empty() {
+            aoc_core::or_exit::<(), _>(Err("there are no slopes to multiply together"), Some(file));
         }
     }
-}
-
-#[derive(Debug)]
-#[derive(PartialEq)]
-struct Size {
-    height: usize,
-    width: usize,
-}
-
-#[derive(Debug)]
-#[derive(PartialEq)]
-struct Coordinate {
-    x: usize,
-    y: usize,
-}
 
-#[derive(Debug)]
-struct Map {
-    grid: Vec<Vec<Space>>,
-}
-impl Map {
-    fn new(str_map: &str) -> Map {
-        let mut map = Map {
-            grid: Vec::new()
-        };
-        
-        for row in str_map.lines() {
-            // populate the map's row
-            let mut row_spaces = Vec::new();
-            for symbol in String::from(row).chars() {
-                row_spaces.push(Space::from(symbol));
-            }
-            map.grid.push(row_spaces);
-        }
-        
-        map
+    let raw_input = aoc_core::load_input(path.as_deref());
+    let multiply = !slopes.is_empty() || slopes_file.is_some();
+    let map = match legend {
+        Some(legend) => aoc_core::or_exit(Map::with_legend(&raw_input, legend), path.as_deref()),
+        None => aoc_core::or_exit(Day3::parse(&raw_input), path.as_deref()),
+    };
+    if !multiply && search.is_none() && render.is_none() && !route {
+        aoc_core::print_answers::<Day3>(&map, aoc_core::Part::Both);
+        return;
     }
-    
-    // returns (height, width)
-    fn size(&self) -> Size {
-        Size {
-            height: self.grid.len(),
-            width: self.grid[0].len()
+
+    let safest = if route { safest_route(&map, &moves) } else { None };
+    if let Some(style) = render {
+        let drawn = if slopes.is_empty() && !route { vec![Slope { right: 3, down: 1 }] } else { slopes.clone() };
+        for slope in drawn {
+            let steps = trajectory(&map, slope);
+            println!("slope {}:\n{}", slope, map.with_path(&steps, style));
         }
-    }
-    
-    fn get(&self, x: usize, y: usize) -> &Space {
-        &self.grid[y][x % self.size().width]
-    }
-}
-impl fmt::Display for Map {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for (y, row) in self.grid.iter().enumerate() {
-            for (x, space) in self.grid[y].iter().enumerate() {
-                write!(f, "{}", space);
-            }
-            writeln!(f, "");
+        if let Some(safest) = &safest {
+            println!("route:\n{}", map.with_path(&safest.steps, style));
         }
-        //TODO: this seems wrong, need to find idiomatic way to append a bunch and aggregate the result
-        return fmt::Result::Ok(())
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_solve_part_1_a() {
-        let expected = 7;
-        let raw_input = "\
-..##.......
-#...#...#..
-.#....#..#.
-..#.#...#.#
-.#...##..#.
-..#.##.....
-.#.#.#....#
-.#........#
-#.##...#...
-#...##....#
-.#..#...#.#";
-        let mut input = Map::new(raw_input);
-
-        assert_eq!(expected, solve_part_1(&input, 3, 1));
+    if multiply {
+        println!("{}", solve_part_2(&map, &slopes));
     }
-
-    #[test]
-    fn test_map_size() {
-        let expected = Size { height: 11, width: 11 };
-        let raw_input = "\
-..##.......
-#...#...#..
-.#....#..#.
-..#.#...#.#
-.#...##..#.
-..#.##.....
-.#.#.#....#
-.#........#
-#.##...#...
-#...##....#
-.#..#...#.#";
-        let map = Map::new(raw_input);
-
-        assert_eq!(expected, map.size());
+    if let Some(max) = search {
+        let found = search_slopes(&map, max);
+        println!("lowest cost: {} moving {}", found.fewest.1, found.fewest.0);
+        println!("highest cost: {} moving {}", found.most.1, found.most.0);
     }
-
-    #[test]
-    fn test_map_index() {
-        let expected = Space::OPEN;
-        let raw_input = "\
-..##.......
-#...#...#..
-.#....#..#.
-..#.#...#.#
-.#...##..#.
-..#.##.....
-.#.#.#....#
-.#........#
-#.##...#...
-#...##....#
-.#..#...#.#";
-        let map = Map::new(raw_input);
-        println!("{}", map);
-        
-        assert_eq!(&expected, map.get(1, 9));
+    if let Some(safest) = safest {
+        println!("safest route: {} over {} steps from column {}", safest.cost, safest.steps.len(), safest.start.x);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,167 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Coordinate, Map, Step};
+
+/// One way the toboggan is allowed to move, written `right,down` (e.g.
+/// `-1,1` for down and to the left).  Unlike a [`crate::Slope`] it can head
+/// left, but it still has to go down.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Move {
+    pub right: isize,
+    pub down: usize,
+}
+impl FromStr for Move {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Move, String> {
+        let (right, down) = s.split_once(',')
+            .ok_or_else(|| format!("expected a move like `-1,1`, found '{}'", s))?;
+        let right = right.trim().parse()
+            .map_err(|_| format!("invalid distance right '{}'", right))?;
+        let down = down.trim().parse()
+            .map_err(|_| format!("invalid distance down '{}'", down))?;
+        if down == 0 {
+            return Err(String::from("a move has to go down at least 1"));
+        }
+        Ok(Move { right, down })
+    }
+}
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.right, self.down)
+    }
+}
+
+/// Straight down, down and to the left, and down and to the right.
+pub const DEFAULT_MOVES: [Move; 3] = [
+    Move { right: 0, down: 1 },
+    Move { right: -1, down: 1 },
+    Move { right: 1, down: 1 },
+];
+
+/// The cheapest way down the map and what it costs.
+#[derive(Debug, PartialEq)]
+pub struct Route {
+    // the square on the top row it sets off from
+    pub start: Coordinate,
+    pub steps: Vec<Step>,
+    pub cost: u64,
+}
+
+/// Finds the route from the top row to the bottom that costs the least
+/// (hits the fewest trees, by default), using any of `moves` at each step.
+/// The route can set off from any square on the top row, which like a
+/// slope's corner isn't counted, and ends with a move off the bottom of the
+/// map.  Moves wrap around the sides of the map, and the steps' `x` are
+/// columns on the map, so the route can be drawn with [`Map::with_path`].
+/// Ties go to the route that starts furthest left, then to the one that uses
+/// earlier moves first.  There's no route without any moves.
+pub fn safest_route(map: &Map, moves: &[Move]) -> Option<Route> {
+    if moves.is_empty() {
+        return None;
+    }
+    let (height, width) = (map.height, map.width);
+
+    // the cheapest way found to land on each square, and where it came from
+    let mut best: Vec<Option<(u64, usize)>> = vec![None; height * width];
+    for (start, square) in best.iter_mut().take(width).enumerate() {
+        *square = Some((0, start));
+    }
+    let mut finish: Option<(u64, usize)> = None;
+
+    for from in 0..height * width {
+        let (cost, _) = match best[from] {
+            Some(best) => best,
+            None => continue,
+        };
+        let (y, x) = (from / width, from % width);
+        for step in moves {
+            if y + step.down >= height {
+                if finish.is_none_or(|(best, _)| cost < best) {
+                    finish = Some((cost, from));
+                }
+                continue;
+            }
+            let column = (x as isize + step.right).rem_euclid(width as isize) as usize;
+            let to = (y + step.down) * width + column;
+            let landed = cost + u64::from(map.tile(column, y + step.down).cost);
+            if best[to].is_none_or(|(best, _)| landed < best) {
+                best[to] = Some((landed, from));
+            }
+        }
+    }
+
+    let (cost, mut at) = finish?;
+    let mut steps = Vec::new();
+    while at >= width {
+        let position = Coordinate { x: at % width, y: at / width };
+        let tile = map.tile(position.x, position.y);
+        steps.push(Step {
+            at: position,
+            space: tile.space,
+            cost: tile.cost,
+        });
+        at = best[at].map(|(_, from)| from).unwrap_or(0);
+    }
+    steps.reverse();
+    let start = Coordinate { x: at, y: 0 };
+    log::debug!("safest route costs {} over {} steps from column {}", cost, steps.len(), start.x);
+
+    Some(Route { start, steps, cost })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{solve_part_1, Space};
+    use aoc_core::Solution;
+
+    #[test]
+    fn test_safest_route() {
+        let raw_input = "\
+..#
+#.#
+##.
+.##";
+        let map = crate::Day3::parse(raw_input).unwrap();
+        let expected = Route {
+            start: Coordinate { x: 0, y: 0 },
+            steps: vec![
+                Step { at: Coordinate { x: 1, y: 1 }, space: Space::OPEN, cost: 0 },
+                Step { at: Coordinate { x: 2, y: 2 }, space: Space::OPEN, cost: 0 },
+                Step { at: Coordinate { x: 0, y: 3 }, space: Space::OPEN, cost: 0 },
+            ],
+            cost: 0,
+        };
+
+        assert_eq!(Some(expected), safest_route(&map, &DEFAULT_MOVES));
+        assert_eq!(2, solve_part_1(&map, 0, 1));
+    }
+
+    #[test]
+    fn test_safest_route_wraps_left() {
+        let raw_input = "\
+...
+##.
+###";
+        let map = crate::Day3::parse(raw_input).unwrap();
+        let moves = vec![Move { right: -1, down: 1 }, Move { right: 0, down: 2 }];
+
+        let route = safest_route(&map, &moves).unwrap();
+
+        // left from the first column lands on the last one, then jumps off
+        assert_eq!(vec![Coordinate { x: 2, y: 1 }],
+            route.steps.iter().map(|s| s.at).collect::<Vec<Coordinate>>());
+        assert_eq!(Coordinate { x: 0, y: 0 }, route.start);
+        assert_eq!(0, route.cost);
+        assert_eq!(None, safest_route(&map, &[]));
+    }
+
+    #[test]
+    fn test_parse_move() {
+        assert_eq!(Ok(Move { right: -2, down: 1 }), "-2,1".parse());
+        assert!("1,0".parse::<Move>().is_err());
+        assert!("1".parse::<Move>().is_err());
+    }
+}
@@ -0,0 +1,79 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use day4::schema::{count_valid, Schema};
+use day4::{parse_records, Mode, PassportParser, Tally};
+
+// A repeatable pseudo-random batch, in the puzzle's layout.  Each field is
+// left out one time in twenty and given a bad value one time in eight, so
+// every path through the validators gets used.
+fn batch(passports: usize) -> String {
+    let mut seed: u64 = 2020;
+    let mut next = move |below: u64| {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (seed >> 33) % below
+    };
+    let mut raw = String::with_capacity(passports * 110);
+    for _ in 0..passports {
+        let fields = [
+            format!("byr:{}", 1900 + next(120)),
+            format!("iyr:{}", 2005 + next(20)),
+            format!("eyr:{}", 2015 + next(20)),
+            match next(8) {
+                0 => format!("hgt:{}", 100 + next(100)),
+                1..=3 => format!("hgt:{}in", 50 + next(30)),
+                _ => format!("hgt:{}cm", 140 + next(60)),
+            },
+            match next(8) {
+                0 => format!("hcl:{:06x}", next(1 << 24)),
+                _ => format!("hcl:#{:06x}", next(1 << 24)),
+            },
+            match next(8) {
+                0 => String::from("ecl:zzz"),
+                _ => format!("ecl:{}", ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"][next(7) as usize]),
+            },
+            match next(8) {
+                0 => format!("pid:{}", next(1_000_000)),
+                _ => format!("pid:{:09}", next(1_000_000_000)),
+            },
+            format!("cid:{}", next(350)),
+        ];
+        for (i, field) in fields.iter().enumerate() {
+            if next(20) == 0 {
+                continue;
+            }
+            raw.push_str(field);
+            raw.push(if i == 3 { '\n' } else { ' ' });
+        }
+        raw.push_str("\n\n");
+    }
+    raw
+}
+
+fn throughput(c: &mut Criterion) {
+    let mut group = c.benchmark_group("passports");
+    group.sample_size(10);
+    let schema = Schema::passport();
+    for &passports in &[10_000, 1_000_000] {
+        let raw = batch(passports);
+        group.throughput(Throughput::Elements(passports as u64));
+
+        group.bench_with_input(BenchmarkId::new("parse", passports), &raw, |b, raw| {
+            b.iter(|| PassportParser::new(raw.as_bytes(), Mode::Strict).map(Result::unwrap).count())
+        });
+        group.bench_with_input(BenchmarkId::new("tally", passports), &raw, |b, raw| {
+            b.iter(|| {
+                let mut tally = Tally::default();
+                for passport in PassportParser::new(raw.as_bytes(), Mode::Strict) {
+                    tally.add(&passport.unwrap());
+                }
+                tally
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("schema", passports), &raw, |b, raw| {
+            b.iter(|| count_valid(&parse_records(raw).unwrap(), &schema))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, throughput);
+criterion_main!(benches);
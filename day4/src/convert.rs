@@ -0,0 +1,295 @@
+use std::io::{self, Read, Write};
+use std::str::FromStr;
+
+use aoc_core::ParseError;
+use serde::{Deserialize, Serialize};
+
+use crate::{FieldError, Location, Mode, Passport, FIELDS};
+
+/// The data formats passports can be converted to and from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// One JSON array of objects.
+    Json,
+    /// One JSON object per line.
+    Ndjson,
+    /// A header row, then one row per passport.
+    Csv,
+}
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        match s {
+            "json" => Ok(Format::Json),
+            "ndjson" => Ok(Format::Ndjson),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("unknown data format '{}', expected json, ndjson or csv", s)),
+        }
+    }
+}
+
+// A passport as one row: a column for each field, named by its puzzle key,
+// and whether it passes part 2's rules if that was asked for.  Fields that
+// aren't passport fields aren't kept.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Row {
+    byr: Option<String>,
+    iyr: Option<String>,
+    eyr: Option<String>,
+    hgt: Option<String>,
+    hcl: Option<String>,
+    ecl: Option<String>,
+    pid: Option<String>,
+    cid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    valid: Option<bool>,
+}
+impl Row {
+    fn new(passport: &Passport, with_status: bool) -> Row {
+        Row {
+            byr: passport.get("byr"),
+            iyr: passport.get("iyr"),
+            eyr: passport.get("eyr"),
+            hgt: passport.get("hgt"),
+            hcl: passport.get("hcl"),
+            ecl: passport.get("ecl"),
+            pid: passport.get("pid"),
+            cid: passport.get("cid"),
+            valid: if with_status { Some(passport.passes_rules()) } else { None },
+        }
+    }
+
+    // in the same order as FIELDS
+    fn values(self) -> [Option<String>; 8] {
+        [self.byr, self.iyr, self.eyr, self.hgt, self.hcl, self.ecl, self.pid, self.cid]
+    }
+
+    // the location is where the row was found, so errors can point back at it
+    fn into_passport(self, location: Location, mode: Mode) -> Result<Passport, ParseError> {
+        let mut passport = Passport::new();
+        passport.location = location;
+        for (field, value) in FIELDS.iter().zip(self.values().iter()) {
+            let value = match value {
+                Some(value) => value,
+                None => continue,
+            };
+            if let Err(message) = passport.set(field.key, value) {
+                let error = ParseError::new(location.first_line, 1, value, message);
+                match mode {
+                    Mode::Strict => return Err(error),
+                    Mode::Lenient => passport.field_errors.push(FieldError {
+                        key: Some(String::from(field.key)),
+                        error,
+                    }),
+                }
+            }
+        }
+        Ok(passport)
+    }
+}
+
+/// Writes passports out as JSON, NDJSON or CSV, one at a time, with a column
+/// for each passport field and, if asked for, a `valid` column saying
+/// whether the passport passes part 2's rules.  Values are written the way
+/// normalising writes them, so hex colours are in lower case.  Missing
+/// fields are `null` in JSON and empty in CSV.  Call [`Exporter::finish`]
+/// once every passport has been written.
+pub struct Exporter<W: Write> {
+    sink: Sink<W>,
+    with_status: bool,
+    // how many passports have been written so far
+    written: usize,
+}
+
+enum Sink<W: Write> {
+    Json(W),
+    Ndjson(W),
+    Csv(Box<csv::Writer<W>>),
+}
+
+impl<W: Write> Exporter<W> {
+    pub fn new(out: W, format: Format, with_status: bool) -> Exporter<W> {
+        let sink = match format {
+            Format::Json => Sink::Json(out),
+            Format::Ndjson => Sink::Ndjson(out),
+            Format::Csv => Sink::Csv(Box::new(csv::Writer::from_writer(out))),
+        };
+        Exporter {
+            sink,
+            with_status,
+            written: 0,
+        }
+    }
+
+    pub fn write(&mut self, passport: &Passport) -> io::Result<()> {
+        let row = Row::new(passport, self.with_status);
+        match &mut self.sink {
+            Sink::Json(out) => {
+                out.write_all(if self.written == 0 { b"[\n  " } else { b",\n  " })?;
+                serde_json::to_writer(&mut *out, &row)?;
+            },
+            Sink::Ndjson(out) => {
+                serde_json::to_writer(&mut *out, &row)?;
+                out.write_all(b"\n")?;
+            },
+            Sink::Csv(out) => out.serialize(&row)?,
+        }
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Closes the JSON array and flushes what's been written.
+    pub fn finish(self) -> io::Result<()> {
+        match self.sink {
+            Sink::Json(mut out) => {
+                out.write_all(if self.written == 0 { b"[]\n" } else { b"\n]\n" })?;
+                out.flush()
+            },
+            Sink::Ndjson(mut out) => out.flush(),
+            Sink::Csv(mut out) => out.flush(),
+        }
+    }
+}
+
+/// Converts a batch of passports into `format`.
+pub fn export(passports: &[Passport], format: Format, with_status: bool) -> String {
+    let mut out = Vec::new();
+    let mut exporter = Exporter::new(&mut out, format, with_status);
+    for passport in passports {
+        exporter.write(passport).expect("writing to memory doesn't fail");
+    }
+    exporter.finish().expect("writing to memory doesn't fail");
+    String::from_utf8(out).expect("passports are written as UTF-8")
+}
+
+/// Reads passports back in from JSON, NDJSON or CSV, as written by
+/// [`Exporter`].  Columns that aren't passport fields, like `valid`, are
+/// ignored.  A year that can't be read stops a strict import, and is kept
+/// as a field error by a lenient one.  Each passport's location is the line
+/// its row is on, or its place in the array for JSON.
+pub fn import<R: Read>(mut reader: R, format: Format, mode: Mode) -> Result<Vec<Passport>, ParseError> {
+    // every row, and the line it's on
+    let rows: Vec<(Row, usize)> = match format {
+        Format::Json => {
+            let rows: Vec<Row> = serde_json::from_reader(reader).map_err(json_error)?;
+            rows.into_iter().enumerate().map(|(i, row)| (row, i + 1)).collect()
+        },
+        Format::Ndjson => {
+            let mut rows = Vec::new();
+            let mut raw_input = String::new();
+            reader.read_to_string(&mut raw_input).map_err(|e| {
+                ParseError::new(1, 1, "", &format!("couldn't read the batch: {}", e))
+            })?;
+            for (i, line) in raw_input.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let row = serde_json::from_str(line).map_err(|e| json_error(e).on_line(i + 1))?;
+                rows.push((row, i + 1));
+            }
+            rows
+        },
+        Format::Csv => {
+            let mut csv = csv::Reader::from_reader(reader);
+            let headers = csv.headers().map_err(csv_error)?.clone();
+            let mut rows = Vec::new();
+            for record in csv.records() {
+                let record = record.map_err(csv_error)?;
+                let line = record.position().map_or(1, |p| p.line() as usize);
+                let row = record.deserialize(Some(&headers))
+                    .map_err(|e| ParseError::new(line, 1, "", &e.to_string()))?;
+                rows.push((row, line));
+            }
+            rows
+        },
+    };
+
+    rows.into_iter()
+        .enumerate()
+        .map(|(i, (row, line))| row.into_passport(Location { record: i + 1, first_line: line, last_line: line }, mode))
+        .collect()
+}
+
+fn json_error(e: serde_json::Error) -> ParseError {
+    ParseError::new(e.line(), e.column(), "", &e.to_string())
+}
+
+fn csv_error(e: csv::Error) -> ParseError {
+    let line = e.position().map_or(1, |p| p.line() as usize);
+    ParseError::new(line, 1, "", &e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_passports;
+
+    const BATCH: &str = "\
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm
+
+hcl:#cfa07d eyr:2025 pid:166559648
+iyr:2011 ecl:brn hgt:59in";
+
+    #[test]
+    fn test_export_csv() {
+        // given
+        let passports = parse_passports(BATCH, Mode::Strict).unwrap();
+        let expected = "\
+byr,iyr,eyr,hgt,hcl,ecl,pid,cid,valid
+1937,2017,2020,183cm,#fffffd,gry,860033327,147,true
+,2011,2025,59in,#cfa07d,brn,166559648,,false
+";
+
+        // when
+        let exported = export(&passports, Format::Csv, true);
+
+        // then
+        assert_eq!(expected, exported);
+    }
+
+    #[test]
+    fn test_export_json() {
+        let passports = parse_passports(BATCH, Mode::Strict).unwrap();
+        let expected = r##"[
+  {"byr":"1937","iyr":"2017","eyr":"2020","hgt":"183cm","hcl":"#fffffd","ecl":"gry","pid":"860033327","cid":"147"},
+  {"byr":null,"iyr":"2011","eyr":"2025","hgt":"59in","hcl":"#cfa07d","ecl":"brn","pid":"166559648","cid":null}
+]
+"##;
+
+        assert_eq!(expected, export(&passports, Format::Json, false));
+        assert_eq!("[]\n", export(&[], Format::Json, false));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let passports = parse_passports(BATCH, Mode::Strict).unwrap();
+
+        for format in [Format::Json, Format::Ndjson, Format::Csv].iter() {
+            let exported = export(&passports, *format, true);
+            let imported = import(exported.as_bytes(), *format, Mode::Strict).unwrap();
+
+            assert_eq!(exported, export(&imported, *format, true));
+        }
+    }
+
+    #[test]
+    fn test_import_errors() {
+        let raw_input = "\
+{\"byr\":\"1937\"}
+
+{\"byr\":\"19x7\",\"hgt\":\"183\"}";
+
+        let error = import(raw_input.as_bytes(), Format::Ndjson, Mode::Strict).unwrap_err();
+        assert_eq!((3, "expected a four digit year"), (error.line, error.message.as_str()));
+
+        let imported = import(raw_input.as_bytes(), Format::Ndjson, Mode::Lenient).unwrap();
+        assert_eq!(Location { record: 2, first_line: 3, last_line: 3 }, imported[1].location());
+        assert_eq!(Some(String::from("183")), imported[1].get("hgt"));
+        assert_eq!(1, imported[1].field_errors().len());
+
+        let error = import("byr,hgt\n1937,183cm,extra\n".as_bytes(), Format::Csv, Mode::Strict).unwrap_err();
+        assert_eq!(2, error.line);
+    }
+}
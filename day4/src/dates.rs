@@ -0,0 +1,118 @@
+use chrono::{Datelike, Local, NaiveDate};
+
+use crate::report::FieldFailure;
+use crate::Passport;
+
+/// Rules that depend on when a passport is checked, or on more than one of
+/// its fields, which the puzzle's fixed year ranges can't express.  A
+/// passport only has years, so a passport is good until the end of its
+/// expiry year and a holder counts as N years old for all of the year they
+/// turn N.  Rules only apply to the years a passport has; missing ones are
+/// reported on their own.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DateRules {
+    // the day the passports are being checked on
+    pub as_of: NaiveDate,
+    pub min_age: Option<u32>,
+}
+impl DateRules {
+    /// Checks passports as of `as_of`, with no minimum age.
+    pub fn new(as_of: NaiveDate) -> DateRules {
+        DateRules {
+            as_of,
+            min_age: None,
+        }
+    }
+
+    /// Checks passports as of today, by the local clock.
+    pub fn today() -> DateRules {
+        DateRules::new(Local::now().date_naive())
+    }
+
+    /// Every rule `passport` breaks.
+    pub fn check(&self, passport: &Passport) -> Vec<FieldFailure> {
+        let year = self.as_of.year();
+        let failure = |field: &str, value: u32, rule: &str, message: String| FieldFailure {
+            field: String::from(field),
+            value: Some(value.to_string()),
+            rule: String::from(rule),
+            message,
+        };
+        let mut failures = Vec::new();
+
+        if let (Some(issued), Some(expires)) = (passport.issue_year, passport.expiration_year) {
+            if issued >= expires {
+                failures.push(failure("iyr", issued, "issued_after_expiry",
+                    format!("issued in {}, not before it expires in {}", issued, expires)));
+            }
+        }
+        if let Some(expires) = passport.expiration_year {
+            if i64::from(expires) < i64::from(year) {
+                failures.push(failure("eyr", expires, "expired",
+                    format!("expired at the end of {}, before {}", expires, self.as_of)));
+            }
+        }
+        if let (Some(born), Some(min_age)) = (passport.birth_year, self.min_age) {
+            let age = i64::from(year) - i64::from(born);
+            if age < i64::from(min_age) {
+                failures.push(failure("byr", born, "too_young",
+                    format!("holder is {} on {}, under {}", age.max(0), self.as_of, min_age)));
+            }
+        }
+        failures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_passports, Mode};
+
+    fn rules(as_of: &str, min_age: Option<u32>) -> DateRules {
+        DateRules {
+            min_age,
+            ..DateRules::new(as_of.parse().unwrap())
+        }
+    }
+
+    #[test]
+    fn test_date_rules() {
+        // given
+        let raw_input = "\
+byr:2004 iyr:2019 eyr:2021
+
+byr:1980 iyr:2020 eyr:2020
+
+hgt:183cm";
+
+        // when
+        let passports = parse_passports(raw_input, Mode::Strict).unwrap();
+        let rules = rules("2021-12-31", Some(18));
+
+        // then
+        let failures: Vec<Vec<(String, String)>> = passports.iter()
+            .map(|passport| rules.check(passport).into_iter().map(|f| (f.field, f.message)).collect())
+            .collect();
+        assert_eq!(vec![
+            vec![(String::from("byr"), String::from("holder is 17 on 2021-12-31, under 18"))],
+            vec![
+                (String::from("iyr"), String::from("issued in 2020, not before it expires in 2020")),
+                (String::from("eyr"), String::from("expired at the end of 2020, before 2021-12-31")),
+            ],
+            vec![],
+        ], failures);
+    }
+
+    #[test]
+    fn test_date_rules_as_of() {
+        let passports = parse_passports("byr:2004 iyr:2019 eyr:2021", Mode::Strict).unwrap();
+
+        let broken = |rules: DateRules| -> Vec<String> {
+            rules.check(&passports[0]).into_iter().map(|f| f.rule).collect()
+        };
+
+        assert_eq!(vec![String::from("expired")], broken(rules("2022-01-01", Some(18))));
+        assert_eq!(vec![String::from("too_young")], broken(rules("2021-01-01", Some(18))));
+        assert!(broken(rules("2021-01-01", None)).is_empty());
+    }
+}
@@ -0,0 +1,189 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Serialize, Serializer};
+
+/// Text that isn't a valid value for its field.  It keeps the text, so a
+/// passport can still show what it was given, along with the validation rule
+/// it breaks and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidValue {
+    pub text: String,
+    pub code: &'static str,
+    pub message: &'static str,
+}
+impl InvalidValue {
+    fn new(text: &str, code: &'static str, message: &'static str) -> InvalidValue {
+        InvalidValue {
+            text: String::from(text),
+            code,
+            message,
+        }
+    }
+}
+impl fmt::Display for InvalidValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+// written as the text that wasn't valid
+impl Serialize for InvalidValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.text)
+    }
+}
+
+/// An RGB colour written `#rrggbb`.  Either case of hex digit is read, and
+/// it's written back in lower case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HexColor {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+impl FromStr for HexColor {
+    type Err = InvalidValue;
+
+    fn from_str(s: &str) -> Result<HexColor, InvalidValue> {
+        let invalid = || InvalidValue::new(s, "invalid_hair_color", "expected # followed by six hex digits");
+        let digits = s.strip_prefix('#').ok_or_else(invalid)?;
+        if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let channel = |at: usize| u8::from_str_radix(&digits[at..at + 2], 16).map_err(|_| invalid());
+        Ok(HexColor {
+            red: channel(0)?,
+            green: channel(2)?,
+            blue: channel(4)?,
+        })
+    }
+}
+impl fmt::Display for HexColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+    }
+}
+impl Serialize for HexColor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// The eye colours a passport can have, written with the puzzle's codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EyeColor {
+    Amber,
+    Blue,
+    Brown,
+    Gray,
+    Green,
+    Hazel,
+    Other,
+}
+impl EyeColor {
+    pub const ALL: [EyeColor; 7] = [
+        EyeColor::Amber,
+        EyeColor::Blue,
+        EyeColor::Brown,
+        EyeColor::Gray,
+        EyeColor::Green,
+        EyeColor::Hazel,
+        EyeColor::Other,
+    ];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            EyeColor::Amber => "amb",
+            EyeColor::Blue => "blu",
+            EyeColor::Brown => "brn",
+            EyeColor::Gray => "gry",
+            EyeColor::Green => "grn",
+            EyeColor::Hazel => "hzl",
+            EyeColor::Other => "oth",
+        }
+    }
+}
+impl FromStr for EyeColor {
+    type Err = InvalidValue;
+
+    fn from_str(s: &str) -> Result<EyeColor, InvalidValue> {
+        EyeColor::ALL.iter()
+            .copied()
+            .find(|color| color.code() == s)
+            .ok_or_else(|| InvalidValue::new(s, "invalid_eye_color", "expected one of amb blu brn gry grn hzl oth"))
+    }
+}
+impl fmt::Display for EyeColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+impl Serialize for EyeColor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+/// A nine digit passport number.  Leading zeros are part of it, so it's
+/// always written with all nine digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PassportId(u32);
+impl PassportId {
+    pub fn number(self) -> u32 {
+        self.0
+    }
+}
+impl FromStr for PassportId {
+    type Err = InvalidValue;
+
+    fn from_str(s: &str) -> Result<PassportId, InvalidValue> {
+        if s.len() != 9 || !s.chars().all(|c| c.is_ascii_digit()) {
+            return Err(InvalidValue::new(s, "invalid_passport_id", "expected a nine digit number"));
+        }
+        Ok(PassportId(s.parse().expect("nine digits fit in a u32")))
+    }
+}
+impl fmt::Display for PassportId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:09}", self.0)
+    }
+}
+impl Serialize for PassportId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_color() {
+        assert_eq!(Ok(HexColor { red: 0x62, green: 0x3a, blue: 0x2f }), "#623a2f".parse());
+        assert_eq!("#fffffd", "#FFFFFD".parse::<HexColor>().unwrap().to_string());
+        for invalid in ["74454a", "#74454", "#74454az", "#1234567"].iter() {
+            assert_eq!(Err(InvalidValue::new(invalid, "invalid_hair_color", "expected # followed by six hex digits")),
+                invalid.parse::<HexColor>());
+        }
+    }
+
+    #[test]
+    fn test_eye_color() {
+        for color in EyeColor::ALL.iter() {
+            assert_eq!(Ok(*color), color.to_string().parse());
+        }
+        assert_eq!("invalid_eye_color", "zzz".parse::<EyeColor>().unwrap_err().code);
+    }
+
+    #[test]
+    fn test_passport_id() {
+        let id: PassportId = "000000001".parse().unwrap();
+
+        assert_eq!(1, id.number());
+        assert_eq!("000000001", id.to_string());
+        assert!("0123456789".parse::<PassportId>().is_err());
+        assert!("186cm".parse::<PassportId>().is_err());
+        assert!("+12345678".parse::<PassportId>().is_err());
+    }
+}
@@ -0,0 +1,200 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use serde::{Serialize, Serializer};
+
+/// The units a height can be given in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Centimeters,
+    Inches,
+}
+impl FromStr for Unit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Unit, String> {
+        match s {
+            "cm" => Ok(Unit::Centimeters),
+            "in" => Ok(Unit::Inches),
+            _ => Err(format!("unknown unit '{}', expected cm or in", s)),
+        }
+    }
+}
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unit::Centimeters => write!(f, "cm"),
+            Unit::Inches => write!(f, "in"),
+        }
+    }
+}
+
+/// A whole number of centimeters or inches, written like `183cm` or `74in`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Height {
+    pub value: u32,
+    pub unit: Unit,
+}
+impl Height {
+    pub fn new(value: u32, unit: Unit) -> Height {
+        Height { value, unit }
+    }
+
+    /// The same height in `unit`, rounded to the nearest whole one.
+    pub fn to(self, unit: Unit) -> Height {
+        // an inch is exactly 2.54cm
+        let value = match (self.unit, unit) {
+            (Unit::Centimeters, Unit::Inches) => (u64::from(self.value) * 100 + 127) / 254,
+            (Unit::Inches, Unit::Centimeters) => (u64::from(self.value) * 254 + 50) / 100,
+            _ => u64::from(self.value),
+        };
+        Height::new(value as u32, unit)
+    }
+}
+impl FromStr for Height {
+    type Err = HeightError;
+
+    fn from_str(s: &str) -> Result<Height, HeightError> {
+        let text = String::from(s);
+        let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (number, unit) = s.split_at(split);
+        if number.is_empty() {
+            return Err(HeightError::NoNumber { text });
+        }
+        if unit.is_empty() {
+            return Err(HeightError::NoUnit { text });
+        }
+        let unit = match unit.parse() {
+            Ok(unit) => unit,
+            Err(_) => return Err(HeightError::UnknownUnit { unit: String::from(unit), text }),
+        };
+        match number.parse() {
+            Ok(value) => Ok(Height::new(value, unit)),
+            Err(_) => Err(HeightError::TooLarge { text }),
+        }
+    }
+}
+impl fmt::Display for Height {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.value, self.unit)
+    }
+}
+// written the way it's read, e.g. `"183cm"`
+impl Serialize for Height {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Why some text isn't a height.  Each one keeps the text, so a passport can
+/// still show what it was given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeightError {
+    NoNumber { text: String },
+    NoUnit { text: String },
+    UnknownUnit { text: String, unit: String },
+    TooLarge { text: String },
+}
+impl HeightError {
+    pub fn text(&self) -> &str {
+        match self {
+            HeightError::NoNumber { text }
+            | HeightError::NoUnit { text }
+            | HeightError::UnknownUnit { text, .. }
+            | HeightError::TooLarge { text } => text,
+        }
+    }
+
+    /// The validation rule the error breaks, as reported for a passport.
+    pub fn code(&self) -> &'static str {
+        match self {
+            HeightError::NoNumber { .. } | HeightError::TooLarge { .. } => "invalid_height_format",
+            HeightError::NoUnit { .. } | HeightError::UnknownUnit { .. } => "invalid_height_unit",
+        }
+    }
+}
+impl fmt::Display for HeightError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeightError::NoNumber { .. } => write!(f, "expected a number followed by cm or in"),
+            HeightError::NoUnit { .. } => write!(f, "expected cm or in after the number"),
+            HeightError::UnknownUnit { unit, .. } => write!(f, "unknown unit '{}', expected cm or in", unit),
+            HeightError::TooLarge { .. } => write!(f, "the number is too large"),
+        }
+    }
+}
+// written as the text that wasn't a height
+impl Serialize for HeightError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.text())
+    }
+}
+
+/// The heights allowed in each unit, both ends included.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeightRanges {
+    pub centimeters: RangeInclusive<u32>,
+    pub inches: RangeInclusive<u32>,
+}
+impl HeightRanges {
+    pub fn range(&self, unit: Unit) -> &RangeInclusive<u32> {
+        match unit {
+            Unit::Centimeters => &self.centimeters,
+            Unit::Inches => &self.inches,
+        }
+    }
+
+    pub fn check(&self, height: Height) -> Result<(), String> {
+        let range = self.range(height.unit);
+        if range.contains(&height.value) {
+            Ok(())
+        } else {
+            Err(format!("out of range {}–{}{}", range.start(), range.end(), height.unit))
+        }
+    }
+}
+
+/// The puzzle's heights: 150–193cm or 59–76in.
+pub const PASSPORT_HEIGHTS: HeightRanges = HeightRanges {
+    centimeters: 150..=193,
+    inches: 59..=76,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_height() {
+        assert_eq!(Ok(Height::new(183, Unit::Centimeters)), "183cm".parse());
+        assert_eq!(Ok(Height::new(59, Unit::Inches)), "059in".parse());
+        assert_eq!("74in", Height::new(74, Unit::Inches).to_string());
+
+        let errors: Vec<String> = ["cm", "170", "170mm", "99999999999cm"].iter()
+            .map(|s| s.parse::<Height>().unwrap_err().to_string())
+            .collect();
+        assert_eq!(vec![
+            "expected a number followed by cm or in",
+            "expected cm or in after the number",
+            "unknown unit 'mm', expected cm or in",
+            "the number is too large",
+        ], errors);
+    }
+
+    #[test]
+    fn test_convert_height() {
+        assert_eq!(Height::new(72, Unit::Inches), Height::new(183, Unit::Centimeters).to(Unit::Inches));
+        assert_eq!(Height::new(188, Unit::Centimeters), Height::new(74, Unit::Inches).to(Unit::Centimeters));
+        assert_eq!(Height::new(74, Unit::Inches), Height::new(74, Unit::Inches).to(Unit::Inches));
+    }
+
+    #[test]
+    fn test_height_ranges() {
+        assert_eq!(Ok(()), PASSPORT_HEIGHTS.check(Height::new(193, Unit::Centimeters)));
+        assert_eq!(Err(String::from("out of range 59–76in")), PASSPORT_HEIGHTS.check(Height::new(77, Unit::Inches)));
+
+        let tall = HeightRanges { centimeters: 180..=250, ..PASSPORT_HEIGHTS };
+        assert_eq!(Err(String::from("out of range 180–250cm")), tall.check(Height::new(170, Unit::Centimeters)));
+    }
+}
@@ -0,0 +1,788 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use serde::Serialize;
+use validator::{Validate, ValidationError};
+use aoc_core::{column_of, ParseError, Solution};
+use fields::{EyeColor, HexColor, InvalidValue, PassportId};
+use height::{Height, HeightError, PASSPORT_HEIGHTS};
+
+pub mod convert;
+pub mod dates;
+pub mod fields;
+pub mod height;
+pub mod normalise;
+pub mod report;
+pub mod schema;
+
+pub struct Day4;
+impl Solution for Day4 {
+    type Input = Vec<Passport>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(raw_input: &str) -> Result<Vec<Passport>, ParseError> {
+        parse_passports(raw_input, Mode::Strict)
+    }
+
+    fn part1(input: &Vec<Passport>) -> u32 {
+        solve_part_1(input)
+    }
+
+    fn part2(input: &Vec<Passport>) -> u32 {
+        solve_part_2(input)
+    }
+}
+
+fn solve_part_1(passports: &[Passport]) -> u32 {
+    let mut valid_count: u32 = 0;
+    for passport in passports {
+        if passport.is_valid() {
+            valid_count += 1;
+        }
+    }
+    valid_count
+}
+
+fn solve_part_2(passports: &[Passport]) -> u32 {
+    let mut valid_count: u32 = 0;
+    for passport in passports {
+        if passes_part_2(passport) {
+            valid_count += 1;
+        }
+    }
+    valid_count
+}
+
+// Whether the passport passes part 2's rules, logging why not; the diagnosis
+// is only built if it's going to be logged.
+fn passes_part_2(passport: &Passport) -> bool {
+    let valid = passport.passes_rules();
+    if !valid && log::log_enabled!(log::Level::Debug) {
+        log::debug!("{}", report::Diagnosis::new(passport));
+    }
+    valid
+}
+
+/// Both parts' answers, counted one passport at a time so a batch never has
+/// to be held in memory.
+#[derive(Debug, Default, PartialEq)]
+pub struct Tally {
+    pub passports: u32,
+    // passports with every required field, and those whose fields pass
+    pub part1: u32,
+    pub part2: u32,
+}
+impl Tally {
+    pub fn add(&mut self, passport: &Passport) {
+        self.passports += 1;
+        if passport.is_valid() {
+            self.part1 += 1;
+        }
+        if passes_part_2(passport) {
+            self.part2 += 1;
+        }
+    }
+}
+
+// Reads a batch one record at a time, along with the line each one starts
+// on.  Records are separated by one or more blank lines, which may hold
+// whitespace, and `\r\n` line endings are fine.
+struct Records<R> {
+    reader: R,
+    // lines read so far, and the one being read
+    line: usize,
+    buf: String,
+    failed: bool,
+}
+impl<R: BufRead> Records<R> {
+    fn new(reader: R) -> Records<R> {
+        Records {
+            reader,
+            line: 0,
+            buf: String::new(),
+            failed: false,
+        }
+    }
+}
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = Result<(String, usize), ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let mut record = String::new();
+        let mut first_line = 0;
+        loop {
+            self.buf.clear();
+            match self.reader.read_line(&mut self.buf) {
+                Ok(0) => break,
+                Ok(_) => self.line += 1,
+                Err(e) => {
+                    self.failed = true;
+                    let message = format!("couldn't read the batch: {}", e);
+                    return Some(Err(ParseError::new(self.line + 1, 1, "", &message)));
+                },
+            }
+
+            let line = self.buf.trim_end();
+            if line.trim_start().is_empty() {
+                if record.is_empty() {
+                    continue;
+                }
+                break;
+            }
+            if record.is_empty() {
+                first_line = self.line;
+            } else {
+                record.push('\n');
+            }
+            record.push_str(line);
+        }
+
+        if record.is_empty() {
+            None
+        } else {
+            Some(Ok((record, first_line)))
+        }
+    }
+}
+
+// Calls `field` with each `key:value` of a record, or the error if it isn't
+// one, along with a way to make an error for any other part of it.
+fn for_each_field<'a, F>(record: &'a str, first_line: usize, mut field: F) -> Result<(), ParseError>
+where F: FnMut(Result<(&'a str, &'a str), ParseError>, &dyn Fn(&str, &str) -> ParseError) -> Result<(), ParseError> {
+    for (i, line) in record.lines().enumerate() {
+        for token in line.split_whitespace() {
+            let error = |part: &str, message: &str| {
+                ParseError::new(first_line + i, column_of(line, part), part, message)
+            };
+            let key_value = token.split_once(':')
+                .ok_or_else(|| error(token, "expected a `key:value` field"));
+            field(key_value, &error)?;
+        }
+    }
+    Ok(())
+}
+
+/// A record's fields as written, before they're checked against anything,
+/// for validating with a [`schema::Schema`].
+#[derive(Debug, PartialEq)]
+pub struct Record {
+    // the line the record starts on
+    pub line: usize,
+    pub fields: Vec<(String, String)>,
+}
+impl Record {
+    /// The value of the first field called `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields.iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Reads a batch of records without knowing what fields they should have.
+pub fn parse_records(batch: &str) -> Result<Vec<Record>, ParseError> {
+    Records::new(batch.as_bytes())
+        .map(|record| {
+            let (record, first_line) = record?;
+            let mut fields = Vec::new();
+            for_each_field(&record, first_line, |key_value, _| {
+                let (key, val) = key_value?;
+                fields.push((String::from(key), String::from(val)));
+                Ok(())
+            })?;
+            Ok(Record { line: first_line, fields })
+        })
+        .collect()
+}
+
+/// How forgiving [`parse_passports`] is about fields it can't make sense of.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    /// Unknown, duplicate and unreadable fields stop the parse with an error.
+    Strict,
+    /// Unknown fields are kept in the passport's `extra` map, duplicates are
+    /// flagged (the last one wins) and unreadable fields are recorded as
+    /// field errors, so every passport gets read.
+    Lenient,
+}
+
+/// Reads a batch of passports, separated by blank lines.
+pub fn parse_passports(batch: &str, mode: Mode) -> Result<Vec<Passport>, ParseError> {
+    PassportParser::new(batch.as_bytes(), mode).collect()
+}
+
+/// Reads passports one at a time from any reader, so a batch of any size can
+/// be checked without holding it all in memory.
+pub struct PassportParser<R> {
+    records: Records<R>,
+    mode: Mode,
+    // how many passports have been read so far
+    record: usize,
+}
+impl<R: BufRead> PassportParser<R> {
+    pub fn new(reader: R, mode: Mode) -> PassportParser<R> {
+        PassportParser {
+            records: Records::new(reader),
+            mode,
+            record: 0,
+        }
+    }
+}
+impl<R: BufRead> Iterator for PassportParser<R> {
+    type Item = Result<Passport, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (passport_str, first_line) = match self.records.next()? {
+            Ok(record) => record,
+            Err(e) => return Some(Err(e)),
+        };
+        self.record += 1;
+        let record = self.record;
+        Some(parse_passport(&passport_str, first_line, self.mode).map(|mut passport| {
+            passport.location.record = record;
+            passport
+        }))
+    }
+}
+
+// Reads one record into a passport.  `first_line` is where it starts in the
+// batch, so errors can point back at the right line.
+fn parse_passport(passport_str: &str, first_line: usize, mode: Mode) -> Result<Passport, ParseError> {
+    let mut passport = Passport::new();
+    passport.location = Location {
+        record: 1,
+        first_line,
+        last_line: first_line + passport_str.lines().count().max(1) - 1,
+    };
+    let mut seen: Vec<&str> = Vec::new();
+    let mut field_errors = Vec::new();
+    for_each_field(passport_str, first_line, |key_value, error| {
+        // a strict parse stops at the first bad field, a lenient one notes it
+        let mut fail = |key: Option<&str>, error: ParseError| match mode {
+            Mode::Strict => Err(error),
+            Mode::Lenient => {
+                field_errors.push(FieldError { key: key.map(String::from), error });
+                Ok(())
+            },
+        };
+
+        let (key, val) = match key_value {
+            Ok(key_value) => key_value,
+            Err(e) => return fail(None, e),
+        };
+        if seen.contains(&key) {
+            match mode {
+                Mode::Strict => return Err(error(key, "duplicate passport field")),
+                Mode::Lenient => passport.duplicates.push(String::from(key)),
+            }
+        }
+        seen.push(key);
+
+        match passport.set(key, val) {
+            Ok(true) => {},
+            Ok(false) if mode == Mode::Lenient => {
+                passport.extra.insert(String::from(key), String::from(val));
+            },
+            Ok(false) => return Err(error(key, "unknown passport field")),
+            Err(message) => return fail(Some(key), error(val, message)),
+        }
+        Ok(())
+    })?;
+    passport.field_errors = field_errors;
+    Ok(passport)
+}
+
+// A custom validation failure that explains the rule it broke.
+fn rule_error(code: &'static str, message: impl Into<Cow<'static, str>>) -> ValidationError {
+    let mut error = ValidationError::new(code);
+    error.message = Some(message.into());
+    error
+}
+
+// For fields whose text didn't parse into their type.
+fn validate_value<T>(value: &Result<T, InvalidValue>) -> Result<(), ValidationError> {
+    match value {
+        Ok(_) => Ok(()),
+        Err(e) => Err(rule_error(e.code, e.message)),
+    }
+}
+
+fn validate_height(height: &Result<Height, HeightError>) -> Result<(), ValidationError> {
+    match height {
+        Ok(height) => PASSPORT_HEIGHTS.check(*height)
+            .map_err(|message| rule_error("invalid_height_value", message)),
+        Err(e) => Err(rule_error(e.code(), e.to_string())),
+    }
+}
+
+/// Where a passport was found in its batch: which record it was, counting
+/// from 1, and the lines it covers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Location {
+    pub record: usize,
+    pub first_line: usize,
+    pub last_line: usize,
+}
+
+/// A passport field's key, its name and whether a passport needs it, in the
+/// order the puzzle lists them.
+pub struct FieldInfo {
+    pub key: &'static str,
+    pub name: &'static str,
+    pub required: bool,
+    // the `Passport` member that holds it
+    member: &'static str,
+}
+
+pub const FIELDS: [FieldInfo; 8] = [
+    FieldInfo { key: "byr", name: "Birth Year", required: true, member: "birth_year" },
+    FieldInfo { key: "iyr", name: "Issue Year", required: true, member: "issue_year" },
+    FieldInfo { key: "eyr", name: "Expiration Year", required: true, member: "expiration_year" },
+    FieldInfo { key: "hgt", name: "Height", required: true, member: "height" },
+    FieldInfo { key: "hcl", name: "Hair Color", required: true, member: "hair_color" },
+    FieldInfo { key: "ecl", name: "Eye Color", required: true, member: "eye_color" },
+    FieldInfo { key: "pid", name: "Passport ID", required: true, member: "passport_id" },
+    FieldInfo { key: "cid", name: "Country ID", required: false, member: "country_id" },
+];
+
+/// A field a lenient parse couldn't read, and the key it was for if it got
+/// as far as having one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldError {
+    pub key: Option<String>,
+    pub error: ParseError,
+}
+
+#[derive(Debug)]
+#[derive(PartialEq)]
+#[derive(Validate)]
+pub struct Passport {
+    location: Location,
+
+    // what a lenient parse found besides the puzzle's fields: fields it
+    // didn't know, keys it saw more than once and fields it couldn't read
+    extra: BTreeMap<String, String>,
+    duplicates: Vec<String>,
+    field_errors: Vec<FieldError>,
+    // keys of years written as `redacted` by a normaliser, which are there
+    // but can't be read
+    redacted: Vec<String>,
+
+    // byr (Birth Year) - four digits; at least 1920 and at most 2002.
+    #[validate(range(min = 1920, max = 2002))]
+    birth_year: Option<u32>,
+    
+    // iyr (Issue Year) - four digits; at least 2010 and at most 2020.
+    #[validate(range(min = 2010, max = 2020))]
+    issue_year: Option<u32>,
+
+    // eyr (Expiration Year) - four digits; at least 2020 and at most 2030.
+    #[validate(range(min = 2020, max = 2030))]
+    expiration_year: Option<u32>,
+
+    // hgt (Height) - a number followed by either cm or in:
+    //    If cm, the number must be at least 150 and at most 193.
+    //    If in, the number must be at least 59 and at most 76.
+    // Text that isn't a height is kept, so it fails here rather than as it's
+    // parsed.
+    #[validate(custom = "validate_height")]
+    height: Option<Result<Height, HeightError>>,
+
+    // hcl (Hair Color) - a # followed by exactly six characters 0-9 or a-f.
+    #[validate(custom = "validate_value")]
+    hair_color: Option<Result<HexColor, InvalidValue>>,
+
+    // ecl (Eye Color) - exactly one of: amb blu brn gry grn hzl oth.
+    #[validate(custom = "validate_value")]
+    eye_color: Option<Result<EyeColor, InvalidValue>>,
+    
+    // pid (Passport ID) - a nine-digit number, including leading zeroes.
+    #[validate(custom = "validate_value")]
+    passport_id: Option<Result<PassportId, InvalidValue>>,
+
+    // cid (Country ID) - ignored, missing or not.
+    country_id: Option<String>,
+}
+impl Passport {
+    fn new() -> Passport {
+        Passport {
+            location: Location::default(),
+            extra: BTreeMap::new(),
+            duplicates: Vec::new(),
+            field_errors: Vec::new(),
+            redacted: Vec::new(),
+            birth_year: Option::None,
+            issue_year: Option::None,
+            expiration_year: Option::None,
+            height: Option::None,
+            hair_color: Option::None,
+            eye_color: Option::None,
+            passport_id: Option::None,
+            country_id: Option::None,
+        }
+    }
+    
+    pub fn location(&self) -> Location {
+        self.location
+    }
+
+    /// Fields that aren't passport fields, kept by a lenient parse.
+    pub fn extra(&self) -> &BTreeMap<String, String> {
+        &self.extra
+    }
+
+    /// Keys that appeared more than once, flagged by a lenient parse.
+    pub fn duplicates(&self) -> &[String] {
+        &self.duplicates
+    }
+
+    /// Fields that a lenient parse couldn't read.
+    pub fn field_errors(&self) -> &[FieldError] {
+        &self.field_errors
+    }
+
+    // Sets the field with puzzle key `key` from its text, returning whether
+    // it's a passport field, or why the text can't be read.  Values that read
+    // but break a rule are kept for validation to report, and so is a
+    // redacted year, so normalised output always reads back in.
+    fn set(&mut self, key: &str, val: &str) -> Result<bool, &'static str> {
+        let year = match key {
+            "byr" | "iyr" | "eyr" => {
+                self.redacted.retain(|redacted| redacted != key);
+                if val.len() == 4 && val.chars().all(|c| c.is_ascii_digit()) {
+                    Some(val.parse().expect("four digits fit in a u32"))
+                } else if val == normalise::REDACTED {
+                    self.redacted.push(String::from(key));
+                    None
+                } else {
+                    return Err("expected a four digit year");
+                }
+            },
+            _ => None,
+        };
+        match key {
+            "byr" => self.birth_year = year,
+            "iyr" => self.issue_year = year,
+            "eyr" => self.expiration_year = year,
+            "hgt" => self.height = Some(val.parse()),
+            "hcl" => self.hair_color = Some(val.parse()),
+            "ecl" => self.eye_color = Some(val.parse()),
+            "pid" => self.passport_id = Some(val.parse()),
+            "cid" => self.country_id = Some(String::from(val)),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// The value of the field with puzzle key `key`, e.g. `byr`.
+    pub fn get(&self, key: &str) -> Option<String> {
+        if self.redacted.iter().any(|redacted| redacted == key) {
+            return Some(String::from(normalise::REDACTED));
+        }
+        match key {
+            "byr" => self.birth_year.map(|year| year.to_string()),
+            "iyr" => self.issue_year.map(|year| year.to_string()),
+            "eyr" => self.expiration_year.map(|year| year.to_string()),
+            "hgt" => self.height.as_ref().map(|height| match height {
+                Ok(height) => height.to_string(),
+                Err(e) => String::from(e.text()),
+            }),
+            "hcl" => text_of(&self.hair_color),
+            "ecl" => text_of(&self.eye_color),
+            "pid" => text_of(&self.passport_id),
+            "cid" => self.country_id.clone(),
+            _ => None,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        let year = |key: &str, year: Option<u32>| year.is_some() || self.redacted.iter().any(|k| k == key);
+        let is_valid = year("byr", self.birth_year)
+            && year("iyr", self.issue_year)
+            && year("eyr", self.expiration_year)
+            && self.height.is_some()
+            && self.hair_color.is_some()
+            && self.eye_color.is_some()
+            && self.passport_id.is_some();
+        
+        // validate the fields
+        
+        // byr (Birth Year) - four digits; at least 1920 and at most 2002.
+        //let birth_year_pattern = Regex::new(r"^\d{4}$").expect("There was an error in the regex pattern!");
+        //is_valid = is_valid && birth_year_pattern.is_match(self.birth_year.unwrap().as_str());
+        
+        // iyr (Issue Year) - four digits; at least 2010 and at most 2020.
+        
+        // eyr (Expiration Year) - four digits; at least 2020 and at most 2030.
+        
+        // hgt (Height) - a number followed by either cm or in:
+        //    If cm, the number must be at least 150 and at most 193.
+        //    If in, the number must be at least 59 and at most 76.
+        
+        // hcl (Hair Color) - a # followed by exactly six characters 0-9 or a-f.
+        
+        // ecl (Eye Color) - exactly one of: amb blu brn gry grn hzl oth.
+        
+        // pid (Passport ID) - a nine-digit number, including leading zeroes.
+        
+        // cid (Country ID) - ignored, missing or not.
+
+        is_valid
+    }
+
+    // Part 2: every required field is there and passes its rule, and the
+    // parser had nothing to report against the passport.
+    pub(crate) fn passes_rules(&self) -> bool {
+        self.is_valid()
+            && self.field_errors.is_empty()
+            && self.duplicates.is_empty()
+            && self.redacted.is_empty()
+            && self.validate().is_ok()
+    }
+}
+
+// A typed field written back out, or the text it couldn't be read from.
+fn text_of<T: std::fmt::Display>(value: &Option<Result<T, InvalidValue>>) -> Option<String> {
+    value.as_ref().map(|value| match value {
+        Ok(value) => value.to_string(),
+        Err(e) => e.text.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use height::Unit;
+
+    #[test]
+    fn test_solve_part_1_a() {
+        let expected = 2;
+        let raw_input = "\
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm
+
+iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
+hcl:#cfa07d byr:1929
+
+hcl:#ae17e1 iyr:2013
+eyr:2024
+ecl:brn pid:760753108 byr:1931
+hgt:179cm
+
+hcl:#cfa07d eyr:2025 pid:166559648
+iyr:2011 ecl:brn hgt:59in";
+
+        assert_eq!(expected, solve_part_1(&Day4::parse(raw_input).unwrap()));
+    }
+
+    #[test]
+    fn test_passport_parser_1() {
+        let expected = 4;
+        let raw_input = "\
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm
+
+iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
+hcl:#cfa07d byr:1929
+
+hcl:#ae17e1 iyr:2013
+eyr:2024
+ecl:brn pid:760753108 byr:1931
+hgt:179cm
+
+hcl:#cfa07d eyr:2025 pid:166559648
+iyr:2011 ecl:brn hgt:59in";
+
+        let parser = PassportParser::new(raw_input.as_bytes(), Mode::Strict);
+
+        assert_eq!(expected, parser.count());
+    }
+
+    #[test]
+    fn test_passport_is_valid() {
+        let passport = Passport {
+            birth_year: Some(1937),
+            issue_year: Some(2017),
+            expiration_year: Some(2020),
+            height: Some("183cm".parse()),
+            hair_color: Some("#fffffd".parse()),
+            eye_color: Some(Ok(EyeColor::Gray)),
+            passport_id: Some("860033327".parse()),
+            country_id: Some(String::from("147")),
+            ..Passport::new()
+        };
+
+        assert!(passport.is_valid());
+    }
+
+    #[test]
+    fn test_passport_is_invalid() {
+        let passport = Passport {
+            birth_year: None,
+            issue_year: Some(2017),
+            expiration_year: Some(2020),
+            height: Some("183cm".parse()),
+            hair_color: Some("#fffffd".parse()),
+            eye_color: Some(Ok(EyeColor::Gray)),
+            passport_id: Some("860033327".parse()),
+            country_id: Some(String::from("147")),
+            ..Passport::new()
+        };
+
+        assert!(!passport.is_valid());
+    }
+
+    #[test]
+    fn test_passport_cid_is_optional() {
+        let passport = Passport {
+            birth_year: Some(1937),
+            issue_year: Some(2017),
+            expiration_year: Some(2020),
+            height: Some("183cm".parse()),
+            hair_color: Some("#fffffd".parse()),
+            eye_color: Some(Ok(EyeColor::Gray)),
+            passport_id: Some("860033327".parse()),
+            country_id: None,
+            ..Passport::new()
+        };
+
+        assert!(passport.is_valid());
+    }
+
+    #[test]
+    fn test_passport_parse() {
+        // given
+        let raw_input = "\
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm";
+        let expected_passport = Passport {
+            location: Location { record: 1, first_line: 1, last_line: 2 },
+            birth_year: Some(1937),
+            issue_year: Some(2017),
+            expiration_year: Some(2020),
+            height: Some("183cm".parse()),
+            hair_color: Some("#fffffd".parse()),
+            eye_color: Some(Ok(EyeColor::Gray)),
+            passport_id: Some("860033327".parse()),
+            country_id: Some(String::from("147")),
+            ..Passport::new()
+        };
+        
+        // when
+        let parsed_passport = parse_passport(raw_input, 1, Mode::Strict);
+
+        assert_eq!(Ok(expected_passport), parsed_passport);
+    }
+
+    #[test]
+    fn test_passport_parse_unknown_field() {
+        let raw_input = "\
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+
+byr:1937 iyr:2017
+cid:147 hgt:183cm zip:90210";
+        let expected = ParseError::new(4, 19, "zip", "unknown passport field");
+
+        let parsed: Result<Vec<Passport>, ParseError> = PassportParser::new(raw_input.as_bytes(), Mode::Strict).collect();
+
+        assert_eq!(Err(expected), parsed);
+    }
+
+    #[test]
+    fn test_passport_parse_bad_year() {
+        let raw_input = "byr:19x7 iyr:2017";
+        let expected = ParseError::new(1, 5, "19x7", "expected a four digit year");
+
+        assert_eq!(Err(expected), parse_passport(raw_input, 1, Mode::Strict));
+    }
+
+    #[test]
+    fn test_passport_parse_lenient() {
+        // given
+        let raw_input = "\
+byr:1937 iyr:20x7 zip:90210 hgt:183cm
+hgt:170cm eyr eyr:2020";
+
+        // when
+        let passport = parse_passport(raw_input, 1, Mode::Lenient).unwrap();
+
+        // then
+        let mut extra = BTreeMap::new();
+        extra.insert(String::from("zip"), String::from("90210"));
+        assert_eq!(&extra, passport.extra());
+        assert_eq!(&[String::from("hgt")], passport.duplicates());
+        assert_eq!(&[
+            FieldError {
+                key: Some(String::from("iyr")),
+                error: ParseError::new(1, 14, "20x7", "expected a four digit year"),
+            },
+            FieldError {
+                key: None,
+                error: ParseError::new(2, 11, "eyr", "expected a `key:value` field"),
+            },
+        ], passport.field_errors());
+        assert_eq!(None, passport.issue_year);
+        assert_eq!(Some(Ok(Height::new(170, Unit::Centimeters))), passport.height);
+        assert_eq!(Some(2020), passport.expiration_year);
+    }
+
+    #[test]
+    fn test_passport_parse_strict_duplicate() {
+        let raw_input = "byr:1937 hgt:183cm byr:1938";
+        let expected = ParseError::new(1, 20, "byr", "duplicate passport field");
+
+        assert_eq!(Err(expected), parse_passport(raw_input, 1, Mode::Strict));
+        assert!(parse_passports(raw_input, Mode::Lenient).is_ok());
+    }
+
+    #[test]
+    fn test_passport_parser_record_boundaries() {
+        // given
+        let raw_input = "\
+\r\n\
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd\r\n\
+byr:1937 iyr:2017 cid:147 hgt:183cm  \r\n\
+  \t\r\n\
+\r\n\
+iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884\r\n\
+hcl:#cfa07d byr:1929\n\
+\n";
+
+        // when
+        let passports: Vec<Passport> = PassportParser::new(raw_input.as_bytes(), Mode::Strict)
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        // then
+        let locations: Vec<Location> = passports.iter().map(|p| p.location()).collect();
+        assert_eq!(vec![
+            Location { record: 1, first_line: 2, last_line: 3 },
+            Location { record: 2, first_line: 6, last_line: 7 },
+        ], locations);
+        assert_eq!(Some(String::from("183cm")), passports[0].get("hgt"));
+        assert_eq!(Some(1929), passports[1].birth_year);
+    }
+
+    #[test]
+    fn test_tally() {
+        let raw_input = "\
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm
+
+iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
+hcl:#cfa07d byr:1929
+
+byr:1920 iyr:2010 eyr:2020 hgt:200cm hcl:#000000 ecl:oth pid:000000000";
+        let mut tally = Tally::default();
+
+        for passport in PassportParser::new(raw_input.as_bytes(), Mode::Strict) {
+            tally.add(&passport.unwrap());
+        }
+
+        assert_eq!(Tally { passports: 3, part1: 2, part2: 1 }, tally);
+    }
+}
@@ -1,348 +1,175 @@
-#[macro_use]
-extern crate lazy_static;
-
-use std::env;
-use std::fs;
-use std::fmt;
-use std::str::Split;
-use regex::{Regex, Captures};
-use itertools::Itertools;
-use validator::{Validate, ValidationError};
-
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    let raw_input = fs::read_to_string(&args[1])
-        .expect("Something went wrong reading the file");
-    
-
-    //TODO: implement the solver function
-    println!("Solution Part 1:  {:?}", solve_part_1(raw_input.as_str()));
-    println!("Solution Part 2:  {:?}", solve_part_2(raw_input.as_str()));
-}
-
-fn parse(raw_input: &str) {
-    raw_input.lines()
-        .map(|s| s)
-        .collect::<Vec<&str>>();
-}
-
-fn solve_part_1(raw_input: &str) -> u32 {
-    let parser = PassportParser::new(raw_input);
-    let mut valid_count: u32 = 0;
-    for passport in parser {
-        if passport.is_valid() {
-            valid_count += 1;
-        }
-    }
-    valid_count
+use std::io::{self, BufWriter, Write};
+
+use aoc_core::cli::{self, Args};
+use aoc_core::logging;
+use aoc_core::report::{Format, Render};
+use aoc_core::ParseError;
+use chrono::NaiveDate;
+use day4::convert::{self, Exporter};
+use day4::dates::DateRules;
+use day4::normalise::{Layout, Normaliser};
+use day4::report::Report;
+use day4::schema::{count_valid, Schema};
+use day4::{parse_records, Mode, Passport, PassportParser, Tally};
+
+fn usage() -> String {
+    format!("\
+Usage: day4 [--import <FORMAT>] [--lenient|--strict] [-v|-q] [INPUT]
+       day4 --schema <FILE> [-v|-q] [INPUT]
+       day4 --report <text|json> [--as-of <DATE>] [--min-age <N>] [OPTIONS] [INPUT]
+       day4 --normalise <line|block> [--drop <KEY>]... [--redact <KEY>]... [OPTIONS] [INPUT]
+       day4 --export <json|ndjson|csv> [--with-status] [OPTIONS] [INPUT]
+
+Without --schema, --report, --normalise or --export, prints the answers to both
+parts of the puzzle.
+
+Options:
+    --schema <FILE>    check the documents against the fields and rules in
+                       FILE instead, as JSON if it ends in `.json` and TOML
+                       otherwise, and print how many are valid; see
+                       day4/schemas/passport.toml.  The other options only
+                       know the puzzle's passports, so --report, --import,
+                       --lenient and --strict can't be used with it
+    --report <FORMAT>  print every passport's record number and lines, the
+                       required fields it's missing and the fields that
+                       break a rule, as text or json
+    --as-of <DATE>     also report passports that expired before DATE
+                       (YYYY-MM-DD, default today) or were issued in or
+                       after the year they expire
+    --min-age <N>      also report holders under N years old as of DATE
+    --normalise <LAYOUT>
+                       write the batch back out with the fields in the
+                       puzzle's order and their values tidied up, hex
+                       colours in lower case and heights without leading
+                       zeros, one passport per line or one field per line
+                       (block)
+    --drop <KEY>       leave the KEY field out when normalising, e.g. cid;
+                       can be repeated
+    --redact <KEY>     replace the KEY field's value with `redacted` when
+                       normalising; can be repeated
+    --export <FORMAT>  write the passports out as a JSON array, one JSON
+                       object per line (ndjson) or CSV, with a column for
+                       each passport field
+    --with-status      add a `valid` column to the export saying whether
+                       each passport passes part 2's rules
+    --import <FORMAT>  read the passports from json, ndjson or csv, as
+                       written by --export, instead of a batch; can't be used
+                       with --schema
+    --lenient          keep going past fields the parser can't read: unknown
+                       fields are kept as extras, repeated fields and
+                       unreadable values are reported against the passport;
+                       can't be used with --schema
+    --strict           stop at the first field the parser can't read (the
+                       default); can't be used with --schema
+{}
+
+Reads the batch of documents from INPUT, or from stdin when INPUT is omitted or `-`.", logging::USAGE)
 }
 
-fn solve_part_2(raw_input: &str) -> u32 {
-    let parser = PassportParser::new(raw_input);
-    let mut valid_count: u32 = 0;
-    for passport in parser {
-        if passport.is_valid() {
-            match passport.validate() {
-                Ok(_) => valid_count += 1,
-                Err(e) => {
-                    println!("Invalid Passport {:?}\n{}", passport, e);
-                },
-            };
-        }
-    }
-    valid_count
-}
-
-struct PassportParser<'a> {
-    batch: Split<'a, &'a str>,
-}
-impl<'a> PassportParser<'a> {
-    fn new(batch: &str) -> PassportParser {
-        PassportParser {
-            batch: batch.split("\n\n"),
-        }
-    }
-
-    fn parse(passport_str: &str) -> Passport {
-        let separator = Regex::new(r"\s").expect("Invalid regex");
-        let mut passport = Passport::new();
-        for token in separator.split(passport_str).into_iter() {
-            if token.trim().is_empty() {
-                continue;
-            }
-            let (key, val) = token.splitn(2, ":").collect_tuple().unwrap();
-            match key {
-                "byr" => passport.birth_year = Some(val.parse().unwrap()),
-                "iyr" => passport.issue_year = Some(val.parse().unwrap()),
-                "eyr" => passport.expiration_year = Some(val.parse().unwrap()),
-                "hgt" => passport.height = Some(String::from(val)),
-                "hcl" => passport.hair_color = Some(String::from(val)),
-                "ecl" => passport.eye_color = Some(String::from(val)),
-                "pid" => passport.passport_id = Some(val.parse().unwrap()),
-                "cid" => passport.country_id = Some(String::from(val)),
-                _ => panic!("Unknown token found during parsing batch files!"),
-            }
-        }
-        passport
-    }
-}
-impl<'a> Iterator for PassportParser<'a> {
-    type Item = Passport;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.batch.next() {
-            None => None,
-            Some(s) => Some(PassportParser::parse(s)),
+fn main() {
+    let usage = usage();
+    let mut args = Args::from_env();
+    logging::init_from_args(&mut args);
+    let schema_file: Option<String> = cli::or_usage(args.value("--schema"), &usage);
+    let format: Option<Format> = cli::or_usage(args.value("--report"), &usage);
+    let layout: Option<Layout> = cli::or_usage(args.value("--normalise"), &usage);
+    let dropped: Vec<String> = cli::or_usage(args.values("--drop"), &usage);
+    let redacted: Vec<String> = cli::or_usage(args.values("--redact"), &usage);
+    let export: Option<convert::Format> = cli::or_usage(args.value("--export"), &usage);
+    let with_status = args.flag(&["--with-status"]);
+    let import: Option<convert::Format> = cli::or_usage(args.value("--import"), &usage);
+    let as_of: Option<NaiveDate> = cli::or_usage(args.value("--as-of"), &usage);
+    let min_age: Option<u32> = cli::or_usage(args.value("--min-age"), &usage);
+    let lenient = args.flag(&["--lenient"]);
+    let strict = args.flag(&["--strict"]);
+    if strict && lenient {
+        cli::exit_with_usage("--lenient and --strict can't be used together", &usage);
+    }
+    let mode = if lenient { Mode::Lenient } else { Mode::Strict };
+    let path = cli::or_usage(args.finish(), &usage);
+    if layout.is_none() && !(dropped.is_empty() && redacted.is_empty()) {
+        cli::exit_with_usage("--drop and --redact only apply with --normalise", &usage);
+    }
+    let date_rules = if as_of.is_some() || min_age.is_some() {
+        if format.is_none() {
+            cli::exit_with_usage("--as-of and --min-age only apply with --report", &usage);
         }
-    }
-}
-
-lazy_static! {
-    static ref RE_PASSPORT_ID: Regex = Regex::new(r"^\d{9}$").unwrap();
-    static ref RE_HEX_COLOR: Regex = Regex::new(r"^#[0-9a-fA-F]{6}$").unwrap();
-}
-
-fn validate_eye_color(eye_color: &str) -> Result<(), ValidationError> {
-    if !["amb", "blu", "brn", "gry", "grn", "hzl", "oth"].contains(&eye_color) {
-        return Err(ValidationError::new("invalid_eye_color"));
-    }
-    Ok(())
-}
-
-fn validate_height(height: &str) -> Result<(), ValidationError> {
-    let height_pattern = Regex::new(r"^(\d+)(in|cm)$").expect("There was an error in the regex pattern!");
-    if !height_pattern.is_match(height) {
-    }
-    match height_pattern.captures(height) {
-        // Access captures groups via Captures::at
-        // Prints Some("2016")
-        Some(x) => {
-            let height_num: u32 = x.get(1).map_or(0, |m| m.as_str().parse::<u32>().unwrap());
-            let height_unit: &str = x.get(2).map_or("", |m| m.as_str());
-            match height_unit {
-                "in" => {
-                    //    If in, the number must be at least 59 and at most 76.
-                    if height_num < 59 || height_num > 76 {
-                        return Err(ValidationError::new("invalid_height_value"));
-                    }
-                },
-                "cm" => {
-                    //    If cm, the number must be at least 150 and at most 193.
-                    if height_num < 150 || height_num > 193 {
-                        return Err(ValidationError::new("invalid_height_value"));
-                    }
-                },
-                _ => return Err(ValidationError::new("invalid_height_unit")),
-            }
+        let mut rules = as_of.map_or_else(DateRules::today, DateRules::new);
+        rules.min_age = min_age;
+        Some(rules)
+    } else {
+        None
+    };
+    if export.is_none() && with_status {
+        cli::exit_with_usage("--with-status only applies with --export", &usage);
+    }
+    let outputs = [schema_file.is_some(), format.is_some(), layout.is_some(), export.is_some()];
+    if outputs.iter().filter(|&&output| output).count() > 1 {
+        cli::exit_with_usage("--schema, --report, --normalise and --export can't be used with each other", &usage);
+    }
+    if import.is_some() && schema_file.is_some() {
+        cli::exit_with_usage("--import can't be used with --schema", &usage);
+    }
+    if (lenient || strict) && schema_file.is_some() {
+        cli::exit_with_usage("--lenient and --strict can't be used with --schema", &usage);
+    }
+
+    if let Some(schema_file) = schema_file {
+        let raw_schema = aoc_core::load_input(Some(&schema_file));
+        let schema = if schema_file.ends_with(".json") {
+            Schema::from_json(&raw_schema)
+        } else {
+            Schema::from_toml(&raw_schema)
+        };
+        let schema = aoc_core::or_exit(schema, Some(&schema_file));
+        let raw_input = aoc_core::load_input(path.as_deref());
+        let records = aoc_core::or_exit(parse_records(&raw_input), path.as_deref());
+        println!("{} of {} {} documents valid", count_valid(&records, &schema), records.len(), schema.name);
+        return;
+    }
+
+    // passports are read as they're needed, so a batch can be any size
+    let reader = aoc_core::or_exit(aoc_core::open_input(path.as_deref()), path.as_deref());
+    let passports: Box<dyn Iterator<Item = Result<Passport, ParseError>>> = match import {
+        Some(import) => {
+            let imported = aoc_core::or_exit(convert::import(reader, import, mode), path.as_deref());
+            Box::new(imported.into_iter().map(Ok))
         },
-        None => return Err(ValidationError::new("invalid_height_format")),
-    }
-    
-    Ok(())
+        None => Box::new(PassportParser::new(reader, mode)),
+    };
+    let mut passports = passports.map(|passport| aoc_core::or_exit(passport, path.as_deref()));
+
+    if let Some(format) = format {
+        let passports: Vec<Passport> = passports.collect();
+        println!("{}", report(&passports, date_rules.as_ref()).render(format));
+        return;
+    }
+
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    // stop quietly if whatever is reading the output goes away
+    let written = if let Some(layout) = layout {
+        let normaliser = Normaliser { layout, dropped, redacted };
+        passports.enumerate().try_for_each(|(i, passport)| {
+            let separator = if i > 0 { "\n" } else { "" };
+            writeln!(out, "{}{}", separator, normaliser.write(&passport))
+        })
+    } else if let Some(export) = export {
+        let mut exporter = Exporter::new(&mut out, export, with_status);
+        passports.try_for_each(|passport| exporter.write(&passport))
+            .and_then(|()| exporter.finish())
+    } else {
+        let mut tally = Tally::default();
+        passports.for_each(|passport| tally.add(&passport));
+        writeln!(out, "Solution Part 1:  {}", tally.part1)
+            .and_then(|()| writeln!(out, "Solution Part 2:  {}", tally.part2))
+    };
+    let _ = written.and_then(|()| out.flush());
 }
 
-#[derive(Debug)]
-#[derive(PartialEq)]
-#[derive(Validate)]
-struct Passport {
-    // byr (Birth Year) - four digits; at least 1920 and at most 2002.
-    #[validate(range(min = 1920, max = 2002))]
-    birth_year: Option<u32>,
-    
-    // iyr (Issue Year) - four digits; at least 2010 and at most 2020.
-    #[validate(range(min = 2010, max = 2020))]
-    issue_year: Option<u32>,
-
-    // eyr (Expiration Year) - four digits; at least 2020 and at most 2030.
-    #[validate(range(min = 2020, max = 2030))]
-    expiration_year: Option<u32>,
-
-    // hgt (Height) - a number followed by either cm or in:
-    //    If cm, the number must be at least 150 and at most 193.
-    //    If in, the number must be at least 59 and at most 76.
-    #[validate(custom = "validate_height")]
-    height: Option<String>,
-
-    // hcl (Hair Color) - a # followed by exactly six characters 0-9 or a-f.
-    #[validate(regex = "RE_HEX_COLOR")]
-    hair_color: Option<String>,
-
-    // ecl (Eye Color) - exactly one of: amb blu brn gry grn hzl oth.
-    #[validate(custom = "validate_eye_color")]
-    eye_color: Option<String>,
-    
-    // pid (Passport ID) - a nine-digit number, including leading zeroes.
-    #[validate(regex = "RE_PASSPORT_ID")]
-    passport_id: Option<String>,
-
-    // cid (Country ID) - ignored, missing or not.
-    country_id: Option<String>,
-}
-impl Passport {
-    fn new() -> Passport {
-        Passport {
-            birth_year: Option::None,
-            issue_year: Option::None,
-            expiration_year: Option::None,
-            height: Option::None,
-            hair_color: Option::None,
-            eye_color: Option::None,
-            passport_id: Option::None,
-            country_id: Option::None,
-        }
-    }
-    
-    fn is_valid(&self) -> bool {
-        let mut is_valid = self.birth_year.is_some()
-            && self.issue_year.is_some()
-            && self.expiration_year.is_some()
-            && self.height.is_some()
-            && self.hair_color.is_some()
-            && self.eye_color.is_some()
-            && self.passport_id.is_some();
-        
-        // validate the fields
-        
-        // byr (Birth Year) - four digits; at least 1920 and at most 2002.
-        //let birth_year_pattern = Regex::new(r"^\d{4}$").expect("There was an error in the regex pattern!");
-        //is_valid = is_valid && birth_year_pattern.is_match(self.birth_year.unwrap().as_str());
-        
-        // iyr (Issue Year) - four digits; at least 2010 and at most 2020.
-        
-        // eyr (Expiration Year) - four digits; at least 2020 and at most 2030.
-        
-        // hgt (Height) - a number followed by either cm or in:
-        //    If cm, the number must be at least 150 and at most 193.
-        //    If in, the number must be at least 59 and at most 76.
-        
-        // hcl (Hair Color) - a # followed by exactly six characters 0-9 or a-f.
-        
-        // ecl (Eye Color) - exactly one of: amb blu brn gry grn hzl oth.
-        
-        // pid (Passport ID) - a nine-digit number, including leading zeroes.
-        
-        // cid (Country ID) - ignored, missing or not.
-
-        is_valid
+fn report(passports: &[Passport], date_rules: Option<&DateRules>) -> Report {
+    match date_rules {
+        Some(rules) => Report::with_dates(passports, rules),
+        None => Report::new(passports),
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_solve_part_1_a() {
-        let expected = 2;
-        let raw_input = "\
-ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
-byr:1937 iyr:2017 cid:147 hgt:183cm
-
-iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
-hcl:#cfa07d byr:1929
-
-hcl:#ae17e1 iyr:2013
-eyr:2024
-ecl:brn pid:760753108 byr:1931
-hgt:179cm
-
-hcl:#cfa07d eyr:2025 pid:166559648
-iyr:2011 ecl:brn hgt:59in";
-
-        assert_eq!(expected, solve_part_1(raw_input));
-    }
-
-    #[test]
-    fn test_passport_parser_1() {
-        let expected = 4;
-        let raw_input = "\
-ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
-byr:1937 iyr:2017 cid:147 hgt:183cm
-
-iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
-hcl:#cfa07d byr:1929
-
-hcl:#ae17e1 iyr:2013
-eyr:2024
-ecl:brn pid:760753108 byr:1931
-hgt:179cm
-
-hcl:#cfa07d eyr:2025 pid:166559648
-iyr:2011 ecl:brn hgt:59in";
-
-        let parser = PassportParser::new(raw_input);
-
-        assert_eq!(expected, parser.count());
-    }
-
-    #[test]
-    fn test_passport_is_valid() {
-        let passport = Passport {
-            birth_year: Some(1937),
-            issue_year: Some(2017),
-            expiration_year: Some(2020),
-            height: Some(String::from("183cm")),
-            hair_color: Some(String::from("#fffffd")),
-            eye_color: Some(String::from("gry")),
-            passport_id: Some(String::from("860033327")),
-            country_id: Some(String::from("147")),
-        };
-
-        assert_eq!(true, passport.is_valid());
-    }
-
-    #[test]
-    fn test_passport_is_invalid() {
-        let passport = Passport {
-            birth_year: None,
-            issue_year: Some(2017),
-            expiration_year: Some(2020),
-            height: Some(String::from("183cm")),
-            hair_color: Some(String::from("#fffffd")),
-            eye_color: Some(String::from("gry")),
-            passport_id: Some(String::from("860033327")),
-            country_id: Some(String::from("147")),
-        };
-
-        assert_eq!(false, passport.is_valid());
-    }
-
-    #[test]
-    fn test_passport_cid_is_optional() {
-        let passport = Passport {
-            birth_year: Some(1937),
-            issue_year: Some(2017),
-            expiration_year: Some(2020),
-            height: Some(String::from("183cm")),
-            hair_color: Some(String::from("#fffffd")),
-            eye_color: Some(String::from("gry")),
-            passport_id: Some(String::from("860033327")),
-            country_id: None,
-        };
-
-        assert_eq!(true, passport.is_valid());
-    }
-
-    #[test]
-    fn test_passport_parse() {
-        // given
-        let raw_input = "\
-ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
-byr:1937 iyr:2017 cid:147 hgt:183cm";
-        let expected_passport = Passport {
-            birth_year: Some(1937),
-            issue_year: Some(2017),
-            expiration_year: Some(2020),
-            height: Some(String::from("183cm")),
-            hair_color: Some(String::from("#fffffd")),
-            eye_color: Some(String::from("gry")),
-            passport_id: Some(String::from("860033327")),
-            country_id: Some(String::from("147")),
-        };
-        
-        // when
-        let parsed_passport = PassportParser::parse(raw_input);
-
-        assert_eq!(expected_passport, parsed_passport);
-    }
-}
\ No newline at end of file
@@ -0,0 +1,182 @@
+use std::str::FromStr;
+
+use crate::{Passport, FIELDS};
+
+/// What a redacted field's value is replaced with.
+pub const REDACTED: &str = "redacted";
+
+/// How a normalised passport is laid out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Layout {
+    /// Every field on one line, separated by single spaces.
+    Line,
+    /// One `key:value` field per line.
+    Block,
+}
+impl FromStr for Layout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Layout, String> {
+        match s {
+            "line" => Ok(Layout::Line),
+            "block" => Ok(Layout::Block),
+            _ => Err(format!("unknown layout '{}', expected line or block", s)),
+        }
+    }
+}
+
+/// Writes passports back out in a canonical form: the puzzle's fields in the
+/// puzzle's order, then any extra fields from a lenient parse in key order,
+/// with each value written the way its type writes it, so normalising also
+/// writes hex colours in lower case (`#FFFFFD` becomes `#fffffd`) and drops
+/// leading zeros from heights (`059in` becomes `59in`).  The output reads
+/// back in with the same parse mode that read the batch, redacted values
+/// included, though a redacted passport is no longer valid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Normaliser {
+    pub layout: Layout,
+    // keys of fields to leave out, and of fields whose values are hidden
+    pub dropped: Vec<String>,
+    pub redacted: Vec<String>,
+}
+impl Normaliser {
+    pub fn new(layout: Layout) -> Normaliser {
+        Normaliser {
+            layout,
+            dropped: Vec::new(),
+            redacted: Vec::new(),
+        }
+    }
+
+    /// The fields that will be written for `passport`, in order.  Values a
+    /// lenient parse couldn't read are kept as they were written.
+    pub fn fields(&self, passport: &Passport) -> Vec<(String, String)> {
+        let unreadable = |key: &str| passport.field_errors().iter()
+            .find(|e| e.key.as_deref() == Some(key))
+            .map(|e| e.error.text.clone());
+        let known = FIELDS.iter()
+            .filter_map(|field| passport.get(field.key)
+                .or_else(|| unreadable(field.key))
+                .map(|value| (String::from(field.key), value)));
+        let extra = passport.extra().iter().map(|(key, value)| (key.clone(), value.clone()));
+
+        known.chain(extra)
+            .filter(|(key, _)| !self.dropped.contains(key))
+            .map(|(key, value)| if self.redacted.contains(&key) {
+                (key, String::from(REDACTED))
+            } else {
+                (key, value)
+            })
+            .collect()
+    }
+
+    /// `passport` as a record, without a trailing newline.
+    pub fn write(&self, passport: &Passport) -> String {
+        let fields: Vec<String> = self.fields(passport).iter()
+            .map(|(key, value)| format!("{}:{}", key, value))
+            .collect();
+        match self.layout {
+            Layout::Line => fields.join(" "),
+            Layout::Block => fields.join("\n"),
+        }
+    }
+
+    /// A batch of passports, with a blank line between records.
+    pub fn write_all(&self, passports: &[Passport]) -> String {
+        let records: Vec<String> = passports.iter().map(|passport| self.write(passport)).collect();
+        records.join("\n\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_passports, Mode, Tally};
+
+    #[test]
+    fn test_normalise_line() {
+        // given
+        let raw_input = "\
+  hgt:0183cm   ecl:gry\tpid:060033327 eyr:2020\r
+hcl:#FFFFFD byr:1937 iyr:2017 cid:147\r
+\r
+   \r
+hcl:#cfa07d byr:1929 cid:350 iyr:2013";
+        let expected = "\
+byr:1937 iyr:2017 eyr:2020 hgt:183cm hcl:#fffffd ecl:gry pid:060033327 cid:147
+
+byr:1929 iyr:2013 hcl:#cfa07d cid:350";
+
+        // when
+        let passports = parse_passports(raw_input, Mode::Strict).unwrap();
+
+        // then
+        assert_eq!(expected, Normaliser::new(Layout::Line).write_all(&passports));
+    }
+
+    #[test]
+    fn test_normalise_block_redacted() {
+        // given
+        let raw_input = "zip:90210 pid:1 byr:20x7 cid:147 ecl:zzz ecl:grn hgt";
+        let mut normaliser = Normaliser::new(Layout::Block);
+        normaliser.dropped.push(String::from("cid"));
+        normaliser.redacted.push(String::from("pid"));
+        let expected = "\
+byr:20x7
+ecl:grn
+pid:redacted
+zip:90210";
+
+        // when
+        let passports = parse_passports(raw_input, Mode::Lenient).unwrap();
+
+        // then
+        assert_eq!(expected, normaliser.write_all(&passports));
+    }
+
+    #[test]
+    fn test_normalise_round_trip_redacted() {
+        let raw_input = "byr:1937 iyr:2017 eyr:2020 hgt:183cm hcl:#fffffd ecl:gry pid:860033327";
+        let mut normaliser = Normaliser::new(Layout::Line);
+        normaliser.redacted.extend(["byr", "pid"].iter().map(|key| String::from(*key)));
+
+        let passports = parse_passports(raw_input, Mode::Strict).unwrap();
+        let normalised = normaliser.write_all(&passports);
+        let reread = parse_passports(&normalised, Mode::Strict).unwrap();
+
+        assert_eq!("byr:redacted iyr:2017 eyr:2020 hgt:183cm hcl:#fffffd ecl:gry pid:redacted", normalised);
+        assert_eq!(normalised, Normaliser::new(Layout::Line).write_all(&reread));
+        // still there for part 1, but no longer valid for part 2
+        assert_eq!(Tally { passports: 1, part1: 1, part2: 0 }, {
+            let mut tally = Tally::default();
+            tally.add(&reread[0]);
+            tally
+        });
+    }
+
+    #[test]
+    fn test_normalise_round_trip() {
+        let raw_input = "\
+iyr:2019
+hcl:#602927 eyr:1967 hgt:170cm
+ecl:grn pid:012533040 byr:1946
+
+
+hcl:#dab227 iyr:2012
+ecl:brn hgt:182cm pid:021572410 eyr:2020 byr:1992 cid:277";
+
+        for layout in [Layout::Line, Layout::Block].iter() {
+            let normaliser = Normaliser::new(*layout);
+            let passports = parse_passports(raw_input, Mode::Strict).unwrap();
+            let normalised = normaliser.write_all(&passports);
+
+            let reread = parse_passports(&normalised, Mode::Strict).unwrap();
+            assert_eq!(normalised, normaliser.write_all(&reread));
+            for (passport, reread) in passports.iter().zip(reread.iter()) {
+                for field in FIELDS.iter() {
+                    assert_eq!(passport.get(field.key), reread.get(field.key));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use aoc_core::report::Render;
+use serde::Serialize;
+use serde_json::Value;
+use validator::{Validate, ValidationError};
+
+use crate::dates::DateRules;
+use crate::{Location, Passport, FIELDS};
+
+/// A field whose value broke one of its rules.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct FieldFailure {
+    pub field: String,
+    pub value: Option<String>,
+    // the validator's name for the rule, e.g. `range` or `invalid_height_value`
+    pub rule: String,
+    pub message: String,
+}
+impl FieldFailure {
+    fn new(field: &str, value: Option<String>, error: &ValidationError) -> FieldFailure {
+        let message = match &error.message {
+            Some(message) => message.to_string(),
+            None if error.code == "range" => format!("out of range {}–{}",
+                number(error.params.get("min")), number(error.params.get("max"))),
+            None => error.code.to_string(),
+        };
+        FieldFailure {
+            field: String::from(field),
+            value,
+            rule: error.code.to_string(),
+            message,
+        }
+    }
+}
+
+// validator keeps range bounds as floats, but ours are all whole years
+fn number(value: Option<&Value>) -> String {
+    match value.and_then(Value::as_f64) {
+        Some(n) if n.fract() == 0.0 => format!("{}", n as i64),
+        Some(n) => n.to_string(),
+        None => String::from("?"),
+    }
+}
+
+/// Everything wrong with one passport, and where it is in the batch.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Diagnosis {
+    #[serde(flatten)]
+    pub location: Location,
+    pub valid: bool,
+    // keys of the required fields that aren't there
+    pub missing: Vec<String>,
+    pub failures: Vec<FieldFailure>,
+    // fields that aren't passport fields, from a lenient parse
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, String>,
+}
+impl Diagnosis {
+    pub fn new(passport: &Passport) -> Diagnosis {
+        let unreadable = |key: &str| passport.field_errors.iter().any(|e| e.key.as_deref() == Some(key));
+        let missing: Vec<String> = FIELDS.iter()
+            .filter(|field| field.required && passport.get(field.key).is_none() && !unreadable(field.key))
+            .map(|field| String::from(field.key))
+            .collect();
+
+        let mut failures = Vec::new();
+        if let Err(errors) = passport.validate() {
+            let errors = errors.field_errors();
+            // go through them in field order, the error map has none
+            for field in FIELDS.iter() {
+                for error in errors.get(field.member).into_iter().flat_map(|e| e.iter()) {
+                    failures.push(FieldFailure::new(field.key, passport.get(field.key), error));
+                }
+            }
+        }
+        for field_error in passport.field_errors.iter() {
+            let error = &field_error.error;
+            let message = format!("{} (line {}, column {})", error.message, error.line, error.column);
+            failures.push(match &field_error.key {
+                Some(key) => FieldFailure {
+                    field: key.clone(),
+                    value: Some(error.text.clone()),
+                    rule: String::from("unreadable"),
+                    message,
+                },
+                None => FieldFailure {
+                    field: error.text.clone(),
+                    value: None,
+                    rule: String::from("malformed"),
+                    message,
+                },
+            });
+        }
+        for key in passport.redacted.iter() {
+            failures.push(FieldFailure {
+                field: key.clone(),
+                value: Some(String::from(crate::normalise::REDACTED)),
+                rule: String::from("redacted"),
+                message: String::from("the value was redacted"),
+            });
+        }
+        for key in passport.duplicates.iter() {
+            failures.push(FieldFailure {
+                field: key.clone(),
+                value: passport.get(key).or_else(|| passport.extra.get(key).cloned()),
+                rule: String::from("duplicate"),
+                message: String::from("appears more than once, the last one is kept"),
+            });
+        }
+
+        Diagnosis {
+            location: passport.location,
+            valid: passport.passes_rules(),
+            missing,
+            failures,
+            extra: passport.extra.clone(),
+        }
+    }
+
+    /// Like [`Diagnosis::new`], but also checks the passport against `rules`.
+    pub fn with_dates(passport: &Passport, rules: &DateRules) -> Diagnosis {
+        let mut diagnosis = Diagnosis::new(passport);
+        let failures = rules.check(passport);
+        diagnosis.valid = diagnosis.valid && failures.is_empty();
+        diagnosis.failures.extend(failures);
+        diagnosis
+    }
+}
+impl fmt::Display for Diagnosis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "passport {} (lines {}-{}): {}", self.location.record,
+            self.location.first_line, self.location.last_line,
+            if self.valid { "valid" } else { "invalid" })?;
+        for key in self.missing.iter() {
+            let name = FIELDS.iter().find(|field| field.key == key).map_or("", |field| field.name);
+            write!(f, "\n    missing {} ({})", key, name)?;
+        }
+        for failure in self.failures.iter() {
+            write!(f, "\n    {}", failure.field)?;
+            if let Some(value) = &failure.value {
+                write!(f, " '{}'", value)?;
+            }
+            write!(f, " fails {}: {}", failure.rule, failure.message)?;
+        }
+        for (key, value) in self.extra.iter() {
+            write!(f, "\n    extra {} '{}'", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// The diagnosis of every passport in a batch, in batch order, with a count
+/// of the valid ones at the end of the text form.
+#[derive(Debug)]
+pub struct Report {
+    pub diagnoses: Vec<Diagnosis>,
+}
+impl Report {
+    pub fn new(passports: &[Passport]) -> Report {
+        Report {
+            diagnoses: passports.iter().map(Diagnosis::new).collect(),
+        }
+    }
+
+    pub fn with_dates(passports: &[Passport], rules: &DateRules) -> Report {
+        Report {
+            diagnoses: passports.iter().map(|passport| Diagnosis::with_dates(passport, rules)).collect(),
+        }
+    }
+}
+impl Render for Report {
+    fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.diagnoses).expect("diagnoses always serialize")
+    }
+}
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for diagnosis in self.diagnoses.iter() {
+            writeln!(f, "{}", diagnosis)?;
+        }
+        let valid = self.diagnoses.iter().filter(|d| d.valid).count();
+        write!(f, "{} of {} passports valid", valid, self.diagnoses.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day4;
+    use aoc_core::report::Format;
+    use aoc_core::Solution;
+
+    #[test]
+    fn test_report_text() {
+        // given
+        let raw_input = "\
+pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980
+hcl:#623a2f
+
+eyr:1972 cid:100
+hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926
+
+hgt:59cm ecl:zzz
+eyr:2038 hcl:74454a iyr:2023";
+        let expected = "\
+passport 1 (lines 1-2): valid
+passport 2 (lines 4-5): invalid
+    eyr '1972' fails range: out of range 2020–2030
+    hgt '170' fails invalid_height_unit: expected cm or in after the number
+    pid '186cm' fails invalid_passport_id: expected a nine digit number
+passport 3 (lines 7-8): invalid
+    missing byr (Birth Year)
+    missing pid (Passport ID)
+    iyr '2023' fails range: out of range 2010–2020
+    eyr '2038' fails range: out of range 2020–2030
+    hgt '59cm' fails invalid_height_value: out of range 150–193cm
+    hcl '74454a' fails invalid_hair_color: expected # followed by six hex digits
+    ecl 'zzz' fails invalid_eye_color: expected one of amb blu brn gry grn hzl oth
+1 of 3 passports valid";
+
+        // when
+        let report = Report::new(&Day4::parse(raw_input).unwrap());
+
+        // then
+        assert_eq!(expected, report.to_string());
+    }
+
+    #[test]
+    fn test_report_json() {
+        // given
+        let raw_input = "\
+byr:2003 iyr:2012 eyr:2030 hgt:74in
+hcl:#623a2f ecl:grn pid:087499704";
+        let expected = r#"[
+  {
+    "record": 1,
+    "first_line": 1,
+    "last_line": 2,
+    "valid": false,
+    "missing": [],
+    "failures": [
+      {
+        "field": "byr",
+        "value": "2003",
+        "rule": "range",
+        "message": "out of range 1920–2002"
+      }
+    ]
+  }
+]"#;
+
+        // when
+        let report = Report::new(&Day4::parse(raw_input).unwrap());
+
+        // then
+        assert_eq!(expected, report.render(Format::Json));
+    }
+
+    #[test]
+    fn test_report_lenient() {
+        // given
+        let raw_input = "\
+byr:1937 iyr:20x7 zip:90210 hgt:183cm
+hgt:170cm eyr hcl:#fffffd ecl:gry pid:860033327";
+        let expected = "\
+passport 1 (lines 1-2): invalid
+    missing eyr (Expiration Year)
+    iyr '20x7' fails unreadable: expected a four digit year (line 1, column 14)
+    eyr fails malformed: expected a `key:value` field (line 2, column 11)
+    hgt '170cm' fails duplicate: appears more than once, the last one is kept
+    extra zip '90210'";
+
+        // when
+        let passports = crate::parse_passports(raw_input, crate::Mode::Lenient).unwrap();
+
+        // then
+        assert_eq!(expected, Diagnosis::new(&passports[0]).to_string());
+    }
+
+    #[test]
+    fn test_report_dates() {
+        // given
+        let raw_input = "\
+pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2020 byr:2002
+hcl:#623a2f";
+        let mut rules = DateRules::new(chrono::NaiveDate::from_ymd_opt(2020, 12, 4).unwrap());
+        rules.min_age = Some(21);
+        let expected = "\
+passport 1 (lines 1-2): invalid
+    byr '2002' fails too_young: holder is 18 on 2020-12-04, under 21
+0 of 1 passports valid";
+
+        // when
+        let passports = Day4::parse(raw_input).unwrap();
+
+        // then
+        assert!(Report::new(&passports).diagnoses[0].valid);
+        assert_eq!(expected, Report::with_dates(&passports, &rules).to_string());
+    }
+}
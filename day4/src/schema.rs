@@ -0,0 +1,349 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::Record;
+
+/// The passport rules from the puzzle, written as a schema.
+pub const PASSPORT_SCHEMA: &str = include_str!("../schemas/passport.toml");
+
+// The schema file as written, before its patterns are compiled.
+#[derive(Debug, Deserialize)]
+struct SchemaFile {
+    name: String,
+    #[serde(default)]
+    allow_unknown: bool,
+    fields: Vec<FieldFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FieldFile {
+    key: String,
+    name: Option<String>,
+    #[serde(default)]
+    required: bool,
+    #[serde(flatten)]
+    rule: RuleFile,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum RuleFile {
+    Integer {
+        digits: Option<usize>,
+        min: Option<i64>,
+        max: Option<i64>,
+    },
+    Text {
+        pattern: Option<String>,
+        one_of: Option<Vec<String>>,
+    },
+    Measure {
+        units: BTreeMap<String, Bounds>,
+    },
+}
+
+/// The allowed range of a number, both ends included.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct Bounds {
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+}
+impl Bounds {
+    fn check(&self, value: i64) -> Result<(), String> {
+        match (self.min, self.max) {
+            (Some(min), _) if value < min => Err(format!("{} is less than {}", value, min)),
+            (_, Some(max)) if value > max => Err(format!("{} is more than {}", value, max)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// What a field's value has to look like.
+#[derive(Debug)]
+pub enum Rule {
+    /// A whole number, optionally with an exact number of digits and bounds.
+    Integer { digits: Option<usize>, bounds: Bounds },
+    /// Any text, optionally matching a pattern or one of a list of values.
+    Text { pattern: Option<Regex>, one_of: Option<Vec<String>> },
+    /// A number followed by a unit, e.g. `183cm`, with bounds for each unit.
+    Measure { units: BTreeMap<String, Bounds> },
+}
+impl Rule {
+    fn check(&self, value: &str) -> Result<(), String> {
+        match self {
+            Rule::Integer { digits, bounds } => {
+                if let Some(digits) = digits {
+                    if value.len() != *digits || !value.chars().all(|c| c.is_ascii_digit()) {
+                        return Err(format!("'{}' isn't a {} digit number", value, digits));
+                    }
+                }
+                let number = value.parse().map_err(|_| format!("'{}' isn't a number", value))?;
+                bounds.check(number)
+            },
+            Rule::Text { pattern, one_of } => {
+                if let Some(pattern) = pattern {
+                    if !pattern.is_match(value) {
+                        return Err(format!("'{}' doesn't match /{}/", value, pattern));
+                    }
+                }
+                if let Some(one_of) = one_of {
+                    if !one_of.iter().any(|allowed| allowed == value) {
+                        return Err(format!("'{}' isn't one of {}", value, one_of.join(", ")));
+                    }
+                }
+                Ok(())
+            },
+            Rule::Measure { units } => {
+                let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+                let (number, unit) = value.split_at(split);
+                let number = number.parse()
+                    .map_err(|_| format!("'{}' doesn't start with a number", value))?;
+                let bounds = units.get(unit).ok_or_else(|| {
+                    let known: Vec<&str> = units.keys().map(String::as_str).collect();
+                    format!("'{}' isn't in {}", value, known.join(" or "))
+                })?;
+                bounds.check(number).map_err(|e| format!("{}{}", e, unit))
+            },
+        }
+    }
+}
+
+/// One field of a document and the rule its value has to follow.
+#[derive(Debug)]
+pub struct Field {
+    pub key: String,
+    pub name: String,
+    pub required: bool,
+    pub rule: Rule,
+}
+
+/// A document type described by a schema file rather than a struct, so the
+/// fields and their rules can change without a recompile.  Schemas are
+/// written in TOML or JSON; see `schemas/passport.toml` for the puzzle's
+/// passports.  The puzzle's answers, reports and exports still use the rules
+/// built into [`crate::Passport`], which that schema is tested to match.
+#[derive(Debug)]
+pub struct Schema {
+    pub name: String,
+    // whether fields the schema doesn't list are let through
+    pub allow_unknown: bool,
+    pub fields: Vec<Field>,
+}
+impl Schema {
+    pub fn from_toml(raw: &str) -> Result<Schema, String> {
+        let file: SchemaFile = toml::from_str(raw).map_err(|e| format!("invalid schema: {}", e))?;
+        Schema::compile(file)
+    }
+
+    pub fn from_json(raw: &str) -> Result<Schema, String> {
+        let file: SchemaFile = serde_json::from_str(raw).map_err(|e| format!("invalid schema: {}", e))?;
+        Schema::compile(file)
+    }
+
+    /// The puzzle's passport rules, see [`PASSPORT_SCHEMA`].
+    pub fn passport() -> Schema {
+        Schema::from_toml(PASSPORT_SCHEMA).expect("the passport schema is valid")
+    }
+
+    fn compile(file: SchemaFile) -> Result<Schema, String> {
+        let mut fields: Vec<Field> = Vec::new();
+        for FieldFile { key, name, required, rule } in file.fields {
+            if fields.iter().any(|f| f.key == key) {
+                return Err(format!("invalid schema: field '{}' is listed twice", key));
+            }
+            let rule = match rule {
+                RuleFile::Integer { digits, min, max } => Rule::Integer {
+                    digits,
+                    bounds: Bounds { min, max },
+                },
+                RuleFile::Text { pattern, one_of } => Rule::Text {
+                    pattern: match pattern {
+                        Some(pattern) => Some(Regex::new(&pattern)
+                            .map_err(|e| format!("invalid schema: bad pattern for '{}': {}", key, e))?),
+                        None => None,
+                    },
+                    one_of,
+                },
+                RuleFile::Measure { units } => Rule::Measure { units },
+            };
+            fields.push(Field {
+                name: name.unwrap_or_else(|| key.clone()),
+                key,
+                required,
+                rule,
+            });
+        }
+
+        Ok(Schema {
+            name: file.name,
+            allow_unknown: file.allow_unknown,
+            fields,
+        })
+    }
+
+    /// Checks a document against the schema, returning every reason it
+    /// fails: missing required fields, unknown fields and values that break
+    /// their field's rule.
+    pub fn check(&self, record: &Record) -> Result<(), Vec<String>> {
+        let mut reasons = Vec::new();
+        for field in self.fields.iter() {
+            match record.get(&field.key) {
+                Some(value) => {
+                    if let Err(reason) = field.rule.check(value) {
+                        reasons.push(format!("{} ({}): {}", field.key, field.name, reason));
+                    }
+                },
+                None if field.required => reasons.push(format!("{} ({}) is missing", field.key, field.name)),
+                None => {},
+            }
+        }
+        if !self.allow_unknown {
+            for (key, _) in record.fields.iter() {
+                if !self.fields.iter().any(|f| &f.key == key) {
+                    reasons.push(format!("{} is not a {} field", key, self.name));
+                }
+            }
+        }
+
+        if reasons.is_empty() {
+            Ok(())
+        } else {
+            Err(reasons)
+        }
+    }
+
+    pub fn is_valid(&self, record: &Record) -> bool {
+        self.check(record).is_ok()
+    }
+}
+
+/// How many of `records` pass every rule in `schema`.
+pub fn count_valid(records: &[Record], schema: &Schema) -> usize {
+    records.iter()
+        .filter(|record| match schema.check(record) {
+            Ok(()) => true,
+            Err(reasons) => {
+                log::debug!("invalid {} on line {}: {}", schema.name, record.line, reasons.join("; "));
+                false
+            },
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_passports, parse_records, Day4, Mode};
+    use aoc_core::Solution;
+
+    #[test]
+    fn test_passport_schema_matches_part_2() {
+        let raw_input = "\
+eyr:1972 cid:100
+hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926
+
+iyr:2019
+hcl:#602927 eyr:1967 hgt:170cm
+ecl:grn pid:012533040 byr:1946
+
+pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980
+hcl:#623a2f
+
+eyr:2029 ecl:blu cid:129 byr:1989
+iyr:2014 pid:896056539 hcl:#a97842 hgt:165cm
+
+hcl:#cfa07d eyr:2025 pid:166559648
+iyr:2011 ecl:brn hgt:59in";
+        let records = parse_records(raw_input).unwrap();
+
+        assert_eq!(2, count_valid(&records, &Schema::passport()));
+        assert_eq!(2, Day4::part2(&Day4::parse(raw_input).unwrap()));
+    }
+
+    #[test]
+    fn test_passport_schema_matches_part_2_at_the_edges() {
+        let valid = [
+            ("byr", "1937"), ("iyr", "2017"), ("eyr", "2020"), ("hgt", "183cm"),
+            ("hcl", "#fffffd"), ("ecl", "gry"), ("pid", "860033327"),
+        ];
+        let edges = [
+            ("byr", "1920"), ("byr", "2002"), ("byr", "1919"), ("byr", "2003"),
+            ("byr", "+1937"), ("byr", "01937"), ("byr", "193"), ("byr", "１９３７"),
+            ("iyr", "2010"), ("iyr", "2020"), ("iyr", "2009"), ("iyr", "2021"),
+            ("eyr", "2020"), ("eyr", "2030"), ("eyr", "2019"), ("eyr", "2031"),
+            ("hgt", "150cm"), ("hgt", "193cm"), ("hgt", "149cm"), ("hgt", "194cm"),
+            ("hgt", "59in"), ("hgt", "76in"), ("hgt", "58in"), ("hgt", "77in"),
+            ("hgt", "0183cm"), ("hgt", "183"), ("hgt", "cm"), ("hgt", "183CM"),
+            ("hgt", "+183cm"), ("hgt", "99999999999cm"),
+            ("hcl", "#FFFFFD"), ("hcl", "#12345"), ("hcl", "#1234567"), ("hcl", "123456"),
+            ("hcl", "#ggggggg"),
+            ("ecl", "amb"), ("ecl", "oth"), ("ecl", "AMB"), ("ecl", "xyz"),
+            ("pid", "000000001"), ("pid", "0123456789"), ("pid", "12345678"), ("pid", "١٢٣٤٥٦٧٨٩"),
+        ];
+        // the valid passport with one field changed to each edge value
+        let raw_input: Vec<String> = edges.iter()
+            .map(|(key, value)| valid.iter()
+                .map(|(k, v)| format!("{}:{}", k, if k == key { value } else { v }))
+                .collect::<Vec<String>>()
+                .join(" "))
+            .collect();
+        let raw_input = raw_input.join("\n\n");
+
+        let records = parse_records(&raw_input).unwrap();
+        let passports = parse_passports(&raw_input, Mode::Lenient).unwrap();
+        let schema = Schema::passport();
+
+        for ((record, passport), edge) in records.iter().zip(passports.iter()).zip(edges.iter()) {
+            assert_eq!(schema.is_valid(record), passport.passes_rules(), "{:?}", edge);
+        }
+    }
+
+    #[test]
+    fn test_check_reasons() {
+        let records = parse_records("byr:1919 hgt:190in ecl:zzz pid:0123456789 zip:90210").unwrap();
+        let expected = vec![
+            String::from("byr (Birth Year): 1919 is less than 1920"),
+            String::from("iyr (Issue Year) is missing"),
+            String::from("eyr (Expiration Year) is missing"),
+            String::from("hgt (Height): 190 is more than 76in"),
+            String::from("hcl (Hair Color) is missing"),
+            String::from("ecl (Eye Color): 'zzz' isn't one of amb, blu, brn, gry, grn, hzl, oth"),
+            String::from("pid (Passport ID): '0123456789' doesn't match /^[0-9]{9}$/"),
+            String::from("zip is not a passport field"),
+        ];
+
+        assert_eq!(Err(expected), Schema::passport().check(&records[0]));
+    }
+
+    #[test]
+    fn test_json_schema() {
+        let schema = Schema::from_json(r#"{
+            "name": "library card",
+            "allow_unknown": true,
+            "fields": [
+                { "key": "num", "required": true, "type": "integer", "digits": 6 },
+                { "key": "len", "type": "measure", "units": { "d": { "max": 21 } } }
+            ]
+        }"#).unwrap();
+        let records = parse_records("num:012345 zip:1\n\nnum:12345 len:22d\n\nlen:3w").unwrap();
+
+        assert!(schema.is_valid(&records[0]));
+        assert_eq!(Err(vec![
+            String::from("num (num): '12345' isn't a 6 digit number"),
+            String::from("len (len): 22 is more than 21d"),
+        ]), schema.check(&records[1]));
+        assert_eq!(Err(vec![
+            String::from("num (num) is missing"),
+            String::from("len (len): '3w' isn't in d"),
+        ]), schema.check(&records[2]));
+    }
+
+    #[test]
+    fn test_bad_schema() {
+        assert!(Schema::from_toml("name = \"x\"\n[[fields]]\nkey = \"a\"\ntype = \"colour\"").is_err());
+        assert!(Schema::from_toml("name = \"x\"\n[[fields]]\nkey = \"a\"\ntype = \"text\"\npattern = \"(\"").is_err());
+        assert!(Schema::from_json(r#"{"name": "x", "fields": [{"key": "a", "type": "text"}, {"key": "a", "type": "text"}]}"#).is_err());
+    }
+}
@@ -0,0 +1,290 @@
+use std::fmt;
+use std::str::FromStr;
+use aoc_core::{ParseError, Solution};
+
+pub struct Day5;
+impl Solution for Day5 {
+    type Input = Vec<TicketNumber>;
+    type Part1 = u32;
+    type Part2 = Plane;
+
+    fn parse(raw_input: &str) -> Result<Vec<TicketNumber>, ParseError> {
+        raw_input.lines()
+            .enumerate()
+            .map(|(i, line)| TicketNumber::new(line).map_err(|e| e.on_line(i + 1)))
+            .collect()
+    }
+
+    fn part1(input: &Vec<TicketNumber>) -> u32 {
+        solve_part_1(input)
+    }
+
+    fn part2(input: &Vec<TicketNumber>) -> Plane {
+        solve_part_2(input)
+    }
+}
+
+fn solve_part_1(tickets: &[TicketNumber]) -> u32 {
+    let mut max = 0;
+    for ticket in tickets {
+        let this_id = ticket.id();
+        if this_id > max {
+            max = this_id;
+        }
+    }
+    max
+}
+
+fn solve_part_2(tickets: &[TicketNumber]) -> Plane {
+    let mut plane = Plane::new();
+    for ticket in tickets {
+        plane.add(ticket);
+    }
+    plane
+}
+
+#[derive(Debug)]
+pub struct Plane {
+    seats: [[char; 8]; 128],
+}
+impl Plane {
+    fn new() -> Plane {
+        Plane {
+            seats: [['.'; 8]; 128],
+        }
+    }
+    
+    fn add(&mut self, ticket: &TicketNumber) {
+        self.seats[ticket.row as usize][ticket.column as usize] = 'X';
+    }
+}
+impl fmt::Display for Plane {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (r, row) in self.seats.iter().enumerate() {
+            write!(f, "{}: ", r)?;
+            for seat in row.iter() {
+                write!(f, "{}", seat)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct TicketNumber {
+    row: u8,
+    column: u8,
+}
+impl TicketNumber {
+    fn new(ticket_number: &str) -> Result<TicketNumber, ParseError> {
+        if ticket_number.len() != 10 {
+            return Err(ParseError::new(1, 1, ticket_number, "expected 7 F/B symbols followed by 3 L/R symbols"));
+        }
+
+        let mut r: (u8, u8) = (0, 127);
+        let mut c: (u8, u8) = (0, 7);
+        for (i, nextchar) in ticket_number.char_indices() {
+            let error = |message: &str| {
+                Err(ParseError::new(1, i + 1, &ticket_number[i..i + nextchar.len_utf8()], message))
+            };
+            if i < 7 {
+                // front and back (rows)
+                match nextchar {
+                    'F' => r = TicketNumber::first_half(r),
+                    'B' => r = TicketNumber::last_half(r),
+                    _ => return error("invalid front/back symbol"),
+                }
+            } else {
+                match nextchar {
+                    'L' => c = TicketNumber::first_half(c),
+                    'R' => c = TicketNumber::last_half(c),
+                    _ => return error("invalid left/right symbol"),
+                }
+            }
+        }
+
+        // ten valid symbols always converge on a single row and column
+        Ok(TicketNumber {
+            row: r.0,
+            column: c.0,
+        })
+    }
+    
+    /// The ticket for the seat in `row` (0 to 127) and `column` (0 to 7).
+    pub fn from_seat(row: u8, column: u8) -> Result<TicketNumber, String> {
+        if row > 127 {
+            return Err(format!("row {} isn't on the plane, expected 0 through 127", row));
+        }
+        if column > 7 {
+            return Err(format!("column {} isn't on the plane, expected 0 through 7", column));
+        }
+        Ok(TicketNumber { row, column })
+    }
+
+    /// The ticket for seat ID `id` (0 to 1023), see [`TicketNumber::id`].
+    pub fn from_id(id: u32) -> Result<TicketNumber, String> {
+        if id > 1023 {
+            return Err(format!("seat ID {} isn't on the plane, expected 0 through 1023", id));
+        }
+        TicketNumber::from_seat((id / 8) as u8, (id % 8) as u8)
+    }
+
+    fn first_half(range: (u8, u8)) -> (u8, u8) {
+        (range.0, range.1 - (((range.1+1) - range.0) / 2))
+    }
+
+    fn last_half(range: (u8, u8)) -> (u8, u8) {
+        (range.0 + (((range.1+1) - range.0) / 2), range.1)
+    }
+    
+    pub fn row(&self) -> u8 {
+        self.row
+    }
+
+    pub fn column(&self) -> u8 {
+        self.column
+    }
+
+    pub fn id(&self) -> u32 {
+        (self.row as u32 * 8u32) + (self.column as u32)
+    }
+}
+impl FromStr for TicketNumber {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<TicketNumber, ParseError> {
+        TicketNumber::new(s)
+    }
+}
+impl fmt::Display for TicketNumber {
+    // the boarding pass code, e.g. BFFFBBFRRR; each symbol picks the back or
+    // right half when its bit of the row or column is set
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for bit in (0..7).rev() {
+            write!(f, "{}", if self.row >> bit & 1 == 1 { 'B' } else { 'F' })?;
+        }
+        for bit in (0..3).rev() {
+            write!(f, "{}", if self.column >> bit & 1 == 1 { 'R' } else { 'L' })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_solve_part_1_a() {
+        // BFFFBBFRRR: row 70, column 7, seat ID 567.
+        let expected = TicketNumber {
+            row: 70,
+            column: 7
+        };
+        let raw_input = "BFFFBBFRRR";
+
+        assert_eq!(567, expected.id());
+        assert_eq!(expected, TicketNumber::new(raw_input).unwrap());
+    }
+
+    #[test]
+    fn test_solve_part_1_b() {
+        // FFFBBBFRRR: row 14, column 7, seat ID 119.
+        let expected = TicketNumber {
+            row: 14,
+            column: 7
+        };
+        let raw_input = "FFFBBBFRRR";
+
+        assert_eq!(119, expected.id());
+        assert_eq!(expected, TicketNumber::new(raw_input).unwrap());
+    }
+
+    #[test]
+    fn test_solve_part_1_c() {
+        // BBFFBBFRLL: row 102, column 4, seat ID 820
+        let expected = TicketNumber {
+            row: 102,
+            column: 4
+        };
+        let raw_input = "BBFFBBFRLL";
+
+        assert_eq!(820, expected.id());
+        assert_eq!(expected, TicketNumber::new(raw_input).unwrap());
+    }
+
+    #[test]
+    fn test_range_first_half_1() {
+        let expected = (0, 63);
+        let raw_input = (0, 127);
+        assert_eq!(expected, TicketNumber::first_half(raw_input));
+    }
+
+    #[test]
+    fn test_range_first_half_2() {
+        let expected = (64, 95);
+        let raw_input = (64, 127);
+        assert_eq!(expected, TicketNumber::first_half(raw_input));
+    }
+
+    #[test]
+    fn test_range_last_half_1() {
+        let expected = (32, 63);
+        let raw_input = (0, 63);
+        assert_eq!(expected, TicketNumber::last_half(raw_input));
+    }
+
+    #[test]
+    fn test_ticket_number_invalid_symbol() {
+        let expected = ParseError::new(1, 9, "F", "invalid left/right symbol");
+
+        assert_eq!(Err(expected), TicketNumber::new("BFFFBBFRFR"));
+    }
+
+    #[test]
+    fn test_ticket_number_wrong_length() {
+        let raw_input = "\
+BFFFBBFRRR
+BFFFBBFRR";
+        let expected = ParseError::new(2, 1, "BFFFBBFRR", "expected 7 F/B symbols followed by 3 L/R symbols");
+
+        assert_eq!(Err(expected), Day5::parse(raw_input).map(|_| ()));
+    }
+
+    #[test]
+    fn test_ticket_number_encode() {
+        let ticket = TicketNumber::from_seat(70, 7).unwrap();
+
+        assert_eq!("BFFFBBFRRR", ticket.to_string());
+        assert_eq!("BBFFBBFRLL", TicketNumber::from_id(820).unwrap().to_string());
+        assert_eq!(Err(String::from("row 128 isn't on the plane, expected 0 through 127")),
+            TicketNumber::from_seat(128, 0));
+        assert!(TicketNumber::from_seat(0, 8).is_err());
+        assert!(TicketNumber::from_id(1024).is_err());
+    }
+
+    #[test]
+    fn test_ticket_number_round_trip() {
+        // there are only 1024 seats, so try every one
+        for id in 0..=1023 {
+            let ticket = TicketNumber::from_id(id).unwrap();
+            let decoded: TicketNumber = ticket.to_string().parse().unwrap();
+
+            assert_eq!(id, decoded.id());
+            assert_eq!((ticket.row(), ticket.column()), (decoded.row(), decoded.column()));
+            assert_eq!(Ok(ticket), TicketNumber::from_seat(decoded.row(), decoded.column()));
+        }
+        assert!(TicketNumber::from_id(1024).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn test_ticket_number_off_the_plane(row in 128u8.., column in 8u8..) {
+            prop_assert!(TicketNumber::from_seat(row, 0).is_err());
+            prop_assert!(TicketNumber::from_seat(0, column).is_err());
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::HashSet;
+use aoc_core::{ParseError, Solution};
+
+pub struct Day6;
+impl Solution for Day6 {
+    // one entry per group, holding each person's answers
+    type Input = Vec<Vec<String>>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(raw_input: &str) -> Result<Vec<Vec<String>>, ParseError> {
+        let mut groups = Vec::new();
+        let mut group: Vec<String> = Vec::new();
+
+        for (i, person) in raw_input.lines().enumerate() {
+            // a blank line ends the current group
+            if person.trim().is_empty() {
+                if !group.is_empty() {
+                    groups.push(group);
+                    group = Vec::new();
+                }
+                continue;
+            }
+
+            if let Some((x, c)) = person.char_indices().find(|(_, c)| !c.is_ascii_lowercase()) {
+                return Err(ParseError::new(i + 1, x + 1, &person[x..x + c.len_utf8()], "expected a question from a to z"));
+            }
+            group.push(String::from(person));
+        }
+        if !group.is_empty() {
+            groups.push(group);
+        }
+
+        Ok(groups)
+    }
+
+    fn part1(input: &Vec<Vec<String>>) -> usize {
+        solve_part_1(input)
+    }
+
+    fn part2(input: &Vec<Vec<String>>) -> usize {
+        solve_part_2(input)
+    }
+}
+
+fn solve_part_1(groups: &[Vec<String>]) -> usize {
+    let mut total = 0;
+    
+    for group in groups {
+        let mut group_answers = HashSet::new();
+        for person in group {
+            for answer in person.chars() {
+                group_answers.insert(answer);
+            }
+        }
+        
+        let group_yes = group_answers.len();
+        total += group_yes;
+    }
+    
+    total
+}
+
+fn solve_part_2(groups: &[Vec<String>]) -> usize {
+    let mut total = 0;
+
+    for group in groups {
+        let mut group_answers = HashSet::new();
+
+        // add the answers from the first one
+        for answer in group[0].chars() {
+            group_answers.insert(answer);
+        }
+        
+        for person in group {
+            //not the first one, remove all of the answers from the set that aren't in this answer too
+            for answer in group_answers.clone() {
+                if !person.contains(answer) {
+                    group_answers.remove(&answer);
+                }
+            }
+        }
+
+        let group_yes = group_answers.len();
+        total += group_yes;
+    }
+
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_part_1_a() {
+        let expected = 11;
+        let raw_input = "\
+abc
+
+a
+b
+c
+
+ab
+ac
+
+a
+a
+a
+a
+
+b";
+
+        assert_eq!(expected, solve_part_1(&Day6::parse(raw_input).unwrap()));
+    }
+
+    #[test]
+    fn test_solve_part_2_a() {
+        let expected = 6;
+        let raw_input = "\
+abc
+
+a
+b
+c
+
+ab
+ac
+
+a
+a
+a
+a
+
+b";
+
+        assert_eq!(expected, solve_part_2(&Day6::parse(raw_input).unwrap()));
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_question() {
+        let raw_input = "\
+abc
+
+a
+b2";
+        let expected = ParseError::new(4, 2, "2", "expected a question from a to z");
+
+        assert_eq!(Err(expected), Day6::parse(raw_input));
+    }
+}
@@ -0,0 +1,522 @@
+#[macro_use]
+extern crate nom;
+
+use std::collections::{HashMap, HashSet};
+
+use nom::{
+    lib::std::str::FromStr,
+    Err,
+    IResult,
+    combinator::map,
+    sequence::tuple,
+    character::complete::digit1,
+    character::complete::space0,
+    bytes::complete::tag,
+    branch::alt,
+};
+use nom::multi::separated_list;
+use aoc_core::{column_of, ParseError, Solution};
+
+pub struct Day7;
+impl Solution for Day7 {
+    type Input = Rules;
+    type Part1 = usize;
+    type Part2 = u128;
+
+    fn parse(raw_input: &str) -> Result<Rules, ParseError> {
+        let rules = Rules::new(raw_input)?;
+        // both parts start from your bag
+        if !rules.bags.contains_key("shiny gold") {
+            return Err(ParseError::new(1, 1, "", "there's no rule for shiny gold bags"));
+        }
+        Ok(rules)
+    }
+
+    fn part1(input: &Rules) -> usize {
+        solve_part_1(input)
+    }
+
+    fn part2(input: &Rules) -> u128 {
+        solve_part_2(input)
+    }
+}
+
+fn solve_part_1(rules: &Rules) -> usize {
+    log::info!("There are {} known bag types.", rules.bags.len());
+    
+    let mut count: usize = 0;
+    for k in rules.bags.keys() {
+        if rules.can_bag_hold(k, "shiny gold") {
+            count += 1;
+        }
+    }
+    count
+}
+
+fn solve_part_2(rules: &Rules) -> u128 {
+    log::info!("There are {} known bag types.", rules.bags.len());
+    rules.total_bags("shiny gold") - 1 // subtract the root bag to answer "how many bags does this one hold?"
+}
+
+pub struct Rules {
+    // Keyed on the rule's bag description, value is a list of bag relationships (ie "5 gold bags", "1 silver bag")
+    bags: HashMap<String, Rule>,
+}
+impl Rules {
+    fn new(raw_input: &str) -> Result<Rules, ParseError> {
+        let mut rules = Rules {
+            bags: HashMap::new(),
+        };
+        // where each rule was written, to point at the bags it names
+        let mut written: Vec<(usize, &str, String)> = Vec::new();
+
+        for (i, line) in raw_input.lines().enumerate() {
+            let error = |rest: &str, message: &str| {
+                ParseError::new(i + 1, column_of(line, rest), rest, message)
+            };
+            match parse_rule(line) {
+                Ok((rest, rule)) => {
+                    if !rest.is_empty() {
+                        return Err(error(rest, "unexpected text after the end of the rule"));
+                    }
+                    written.push((i + 1, line, rule.description.clone()));
+                    rules.bags.insert(rule.description.clone(), rule);
+                },
+                Err(Err::Error((rest, _))) | Err(Err::Failure((rest, _))) => {
+                    return Err(error(rest, "expected a rule like `light red bags contain 1 bright white bag.`"));
+                },
+                Err(Err::Incomplete(_)) => {
+                    return Err(error(&line[line.len()..], "the rule ends too early"));
+                },
+            }
+        }
+
+        // every bag a rule says it holds needs a rule of its own
+        for (line_number, line, description) in written.iter() {
+            let holds = match &rules.bags[description].holds {
+                Some(holds) => holds,
+                None => continue,
+            };
+            let missing = holds.keys()
+                .filter(|to| !rules.bags.contains_key(*to))
+                .map(|to| held_name(line, to))
+                .min_by_key(|name| column_of(line, name));
+            if let Some(name) = missing {
+                return Err(ParseError::new(*line_number, column_of(line, name), name, "there's no rule for these bags"));
+            }
+        }
+
+        // and no bag can end up inside itself, or there'd be no end to the counting
+        let mut checked = HashSet::new();
+        for (_, _, description) in written.iter() {
+            if let Some(cycle) = rules.find_cycle(description, &mut Vec::new(), &mut checked) {
+                let holder = cycle[cycle.len() - 2];
+                let (line_number, line, _) = written.iter().find(|(_, _, d)| d == holder).unwrap();
+                let name = held_name(line, cycle[cycle.len() - 1]);
+                let message = format!("bags can't hold themselves: {} bags hold {} bags",
+                    cycle[0], cycle[1..].join(" bags, which hold "));
+                return Err(ParseError::new(*line_number, column_of(line, name), name, &message));
+            }
+        }
+
+        Ok(rules)
+    }
+
+    // The bags from `bag` back round to a bag already on `path`, if there's a
+    // way round.  Bags in `checked` are known not to lead round.
+    fn find_cycle<'a>(&'a self, bag: &'a str, path: &mut Vec<&'a str>, checked: &mut HashSet<&'a str>) -> Option<Vec<&'a str>> {
+        if let Some(start) = path.iter().position(|b| *b == bag) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(bag);
+            return Some(cycle);
+        }
+        if checked.contains(bag) {
+            return None;
+        }
+        path.push(bag);
+        if let Some(holds) = &self.bags[bag].holds {
+            // in a fixed order, so the same cycle is always the one reported
+            let mut held: Vec<&String> = holds.keys().collect();
+            held.sort();
+            for to in held {
+                if let Some(cycle) = self.find_cycle(to, path, checked) {
+                    return Some(cycle);
+                }
+            }
+        }
+        path.pop();
+        checked.insert(bag);
+        None
+    }
+
+    fn can_bag_hold(&self, container: &str, contained_bag: &str) -> bool {
+        self.can_bag_hold_helper(container, contained_bag, 0)
+    }
+    // `depth` is only used to indent the trace output
+    fn can_bag_hold_helper(&self, container: &str, contained_bag: &str, depth: usize) -> bool {
+        let rule = self.bags.get(container).unwrap();
+
+        match &rule.holds {
+            None => false,
+            Some(relationships) => {
+                if relationships.contains_key(contained_bag) {
+                    log::trace!("{:indent$}A {} bag, which can hold your {} bag directly.", "", container, contained_bag, indent = depth * 2);
+                    true
+                } else {
+                    // search the bags this one can contain
+                    log::trace!("{:indent$}Searching inside {} bags", "", container, indent = depth * 2);
+                    relationships.keys()
+                        .any(|k| self.can_bag_hold_helper(k, contained_bag, depth + 1))
+                }
+            }
+        }
+    }
+
+    fn total_bags(&self, container: &str) -> u128 {
+        let rule = self.bags.get(container).unwrap();
+
+        match &rule.holds {
+            None => {
+                log::trace!("{} bags don't hold any additional bags", container);
+                1
+            },
+            Some(relationships) => {
+                log::trace!("{} bags hold {} additional bags", container, relationships.len());
+                // 1 + relationships.len() as u128
+                relationships.values().fold(1, |c, r| {
+                    c + ((r.count as u128) * self.total_bags(&r.to))
+                })
+            }
+        }
+    }
+}
+
+// Where the bag `to` is named in a rule's line, so errors can point at it, or
+// all of the rule's contents when the name can't be picked out.
+fn held_name<'a>(line: &'a str, to: &str) -> &'a str {
+    let contents = &line[line.find(" contain ").unwrap_or(0)..];
+    match contents.find(&format!(" {} bag", to)) {
+        Some(at) => &contents[at + 1..at + 1 + to.len()],
+        None => contents,
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct Rule {
+    description: String,
+    holds: Option<HashMap<String, BagRelationship>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct BagRelationship {
+    count: usize,
+    to: String,
+}
+
+named!(bag_count<&str, u16>,
+    alt!(
+        map_res!(digit1, FromStr::from_str) |
+        map!(tag!("no"), |_| 0u16)
+    )
+);
+
+named!(bag_description<&str, &str>,
+    take_until!(" bag")
+);
+
+// macro doesn't seem to work :(
+// named!(bags<&str, &str>,    
+//     alt!(tag!("bags") | tag!("bag"))
+// );
+
+fn bags(input: &str) -> IResult<&str, &str> {
+    alt((tag("bags"), tag("bag")))(input)
+}
+
+fn bag_holds(input: &str) -> IResult<&str, Option<BagRelationship>> {
+    // example input:
+    //    5 faded blue bags
+    //    1 dark olive bag
+    //    no other bags
+
+    let (input, (count, _, description, _, _)) = tuple((
+        bag_count,
+        space0,
+        bag_description,
+        space0,
+        bags
+    ))(input)?;
+    
+    if count == 0 {
+        Ok((input, None))
+    } else {
+        Ok((input, Some(BagRelationship {
+            count: count as usize,
+            to: String::from(description),
+        })))
+    }
+}
+
+fn parse_rule(input: &str) -> IResult<&str, Rule> {
+    // example input:
+    //    light red bags contain 1 bright white bag, 2 muted yellow bags.
+    //    bright white bags contain 1 shiny gold bag.
+    //    faded blue bags contain no other bags.
+    
+    let (input, (description, _, _, _, _, relationships, _)) = tuple((
+        bag_description,
+        space0,
+        bags,
+        space0,
+        tag("contain "),
+        map(separated_list(tag(", "), bag_holds), |v| {
+            v.into_iter().fold(HashMap::new(), |mut m, r| {
+                match r {
+                    Some(holds) => {
+                        let holds_desc = holds.to.clone();
+                        m.insert(holds_desc, holds);
+                        m
+                    },
+                    None => m,
+                }
+            })
+        }),
+        tag("."),
+    ))(input)?;
+    
+    if relationships.is_empty() {
+        Ok((input, Rule {
+            description: String::from(description),
+            holds: None,
+        }))
+    } else {
+        Ok((input, Rule {
+            description: String::from(description),
+            holds: Some(relationships),
+        }))
+    }
+    
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_part_1_a() {
+        let expected = 4;
+        let raw_input = "\
+light red bags contain 1 bright white bag, 2 muted yellow bags.
+dark orange bags contain 3 bright white bags, 4 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
+dark olive bags contain 3 faded blue bags, 4 dotted black bags.
+vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
+faded blue bags contain no other bags.
+dotted black bags contain no other bags.";
+        let rules = Rules::new(raw_input).unwrap();
+
+        assert_eq!(expected, solve_part_1(&rules));
+    }
+
+    #[test]
+    fn test_solve_part_2_a() {
+        let expected = 126;
+        let raw_input = "\
+shiny gold bags contain 2 dark red bags.
+dark red bags contain 2 dark orange bags.
+dark orange bags contain 2 dark yellow bags.
+dark yellow bags contain 2 dark green bags.
+dark green bags contain 2 dark blue bags.
+dark blue bags contain 2 dark violet bags.
+dark violet bags contain no other bags.";
+        let rules = Rules::new(raw_input).unwrap();
+
+        assert_eq!(expected, solve_part_2(&rules));
+    }
+
+    #[test]
+    fn test_parse_bag_holds_multi() {
+        // given
+        let expected = Ok(("", Some(BagRelationship {
+            count: 3,
+            to: String::from("bright white"),
+        })));
+        let raw_input = "3 bright white bags";
+        
+        // when
+        let result = bag_holds(raw_input);
+        
+        // then
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_parse_bag_holds_single() {
+        // given
+        let expected = Ok(("", Some(BagRelationship {
+            count: 1,
+            to: String::from("silvery grey"),
+        })));
+        let raw_input = "1 silvery grey bag";
+
+        // when
+        let result = bag_holds(raw_input);
+
+        // then
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_parse_bag_holds_no_bags() {
+        // given
+        let expected = Ok(("", Option::None));
+        let raw_input = "no other bags";
+
+        // when
+        let result = bag_holds(raw_input);
+
+        // then
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_parse_rule_many_relationships() {
+        // given
+        let mut expected_relationships = HashMap::new();
+        expected_relationships.insert(String::from("bright white"), BagRelationship {
+            count: 1,
+            to: String::from("bright white"),
+        });
+        expected_relationships.insert(String::from("muted yellow"), BagRelationship {
+            count: 2,
+            to: String::from("muted yellow"),
+        });
+        let expected = Ok(("", Rule {
+            description: String::from("light red"),
+            holds: Some(expected_relationships)
+        }));
+        let raw_input = "light red bags contain 1 bright white bag, 2 muted yellow bags.";
+
+        // when
+        let result = parse_rule(raw_input);
+
+        // then
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_parse_rule_one_relationship() {
+        // given
+        let mut expected_relationships = HashMap::new();
+        expected_relationships.insert(String::from("shiny gold"), BagRelationship {
+            count: 1,
+            to: String::from("shiny gold"),
+        });
+        let expected = Ok(("", Rule {
+            description: String::from("bright white"),
+            holds: Some(expected_relationships)
+        }));
+        let raw_input = "bright white bags contain 1 shiny gold bag.";
+
+        // when
+        let result = parse_rule(raw_input);
+
+        // then
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_parse_rule_no_relationships() {
+        // given
+        let expected = Ok(("", Rule {
+            description: String::from("faded blue"),
+            holds: None
+        }));
+        let raw_input = "faded blue bags contain no other bags.";
+
+        // when
+        let result = parse_rule(raw_input);
+
+        // then
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_rules_report_bad_rule() {
+        // given
+        let raw_input = "\
+faded blue bags contain no other bags.
+bright white bags contain one shiny gold bag.";
+        let expected = ParseError::new(2, 27, "one shiny gold bag.", "expected a rule like `light red bags contain 1 bright white bag.`");
+
+        // when
+        let result = Rules::new(raw_input);
+
+        // then
+        assert_eq!(Some(expected), result.err());
+    }
+
+    #[test]
+    fn test_rules_report_trailing_text() {
+        // given
+        let raw_input = "faded blue bags contain no other bags. extra";
+        let expected = ParseError::new(1, 39, " extra", "unexpected text after the end of the rule");
+
+        // when
+        let result = Rules::new(raw_input);
+
+        // then
+        assert_eq!(Some(expected), result.err());
+    }
+
+    #[test]
+    fn test_rules_report_missing_rule() {
+        // given
+        let raw_input = "\
+faded blue bags contain no other bags.
+shiny gold bags contain 1 faded blue bag, 2 dark blue bags.";
+        let expected = ParseError::new(2, 45, "dark blue", "there's no rule for these bags");
+
+        // when
+        let result = Rules::new(raw_input);
+
+        // then
+        assert_eq!(Some(expected), result.err());
+    }
+
+    #[test]
+    fn test_rules_report_missing_shiny_gold() {
+        let expected = ParseError::new(1, 1, "", "there's no rule for shiny gold bags");
+
+        assert_eq!(Some(expected.clone()), Day7::parse("").err());
+        assert_eq!(Some(expected), Day7::parse("faded blue bags contain no other bags.").err());
+    }
+
+    #[test]
+    fn test_rules_report_missing_rule_without_space() {
+        let raw_input = "shiny gold bags contain 2dark blue bags.";
+
+        let error = Rules::new(raw_input).err().unwrap();
+
+        assert_eq!((1, 16, "there's no rule for these bags"), (error.line, error.column, error.message.as_str()));
+    }
+
+    #[test]
+    fn test_rules_report_cycle() {
+        // given
+        let raw_input = "\
+shiny gold bags contain 2 dark blue bags.
+dark blue bags contain 1 faded blue bag, 1 shiny gold bag.
+faded blue bags contain no other bags.";
+        let expected = ParseError::new(2, 44, "shiny gold",
+            "bags can't hold themselves: shiny gold bags hold dark blue bags, which hold shiny gold bags");
+
+        // when
+        let result = Rules::new(raw_input);
+
+        // then
+        assert_eq!(Some(expected), result.err());
+    }
+}